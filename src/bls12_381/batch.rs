@@ -0,0 +1,180 @@
+use ark_bls12_381::{Fr, G1Affine as NativeG1};
+use ark_ec::{AffineRepr, CurveGroup};
+use circuit_std_rs::big_int::to_binary;
+use expander_compiler::{
+    declare_circuit,
+    frontend::{GenericDefine, M31Config, RootAPI, Variable, M31},
+};
+
+use crate::bls12_381::{ciphersuite::Ciphersuite, g1::*, g2::*, native, pairing::Pairing};
+use crate::hash::sha256::sha256;
+
+pub const BLS_BATCH_SIZE: usize = 16;
+pub const BLS_BATCH_MSG_LEN: usize = 32;
+
+pub const BLS_BATCH_CIPHERSUITE: Ciphersuite = Ciphersuite::G2Pop;
+
+// r_i are 128-bit with the top bit set, leaving 127 random bits. The transcript is hashed in
+// circuit, so a prover can re-roll a forged batch offline until two r_i collide; 2^-127 keeps
+// that out of reach, and the top bit keeps G1::scalar_mul_bits and G2::scalar_mul_bits away
+// from their edge cases.
+const RANDOMIZER_BYTES: usize = 16;
+
+declare_circuit!(BLSBatchVerifyCircuit {
+    pub_keys: [[[Variable; 48]; 2]; BLS_BATCH_SIZE],
+    msgs: [[Variable; BLS_BATCH_MSG_LEN]; BLS_BATCH_SIZE],
+    sigs: [[[[Variable; 48]; 2]; 2]; BLS_BATCH_SIZE],
+});
+
+// Checks N independent signatures with one multi-pairing,
+//   e(-g1, sum [r_i]sig_i) * prod e([r_i]pk_i, H(m_i)) = 1,
+// so the N + 1 Miller loops share a single final exponentiation. The r_i are derived from a
+// SHA-256 transcript of every (pk_i, m_i, sig_i), which fixes them only after all signatures
+// are, so a prover cannot pick invalid signatures whose errors cancel.
+impl GenericDefine<M31Config> for BLSBatchVerifyCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut pairing = Pairing::new(builder);
+        let mut g1 = G1::new(builder);
+        let mut g2 = G2::new(builder);
+
+        let mut pub_keys = vec![];
+        let mut sigs = vec![];
+        let mut transcript = vec![];
+        for i in 0..BLS_BATCH_SIZE {
+            let pk = G1Affine::from_vars(self.pub_keys[i][0].to_vec(), self.pub_keys[i][1].to_vec());
+            g1.assert_on_curve(builder, &pk);
+            g1.assert_in_subgroup(builder, &pk);
+            let sig = G2AffP::from_vars(
+                self.sigs[i][0][0].to_vec(),
+                self.sigs[i][0][1].to_vec(),
+                self.sigs[i][1][0].to_vec(),
+                self.sigs[i][1][1].to_vec(),
+            );
+            g2.assert_on_curve(builder, &sig);
+            g2.assert_in_subgroup(builder, &sig);
+            pub_keys.push(pk);
+            sigs.push(sig);
+
+            transcript.extend_from_slice(&self.pub_keys[i].concat());
+            transcript.extend_from_slice(&self.msgs[i]);
+            transcript.extend_from_slice(&self.sigs[i].concat().concat());
+        }
+
+        //r_i are the 16-byte chunks of SHA-256(seed || j) for j = 0, 1, .., two per output
+        let seed = sha256(builder, &transcript);
+        let one = builder.constant(1);
+        let mut rs = vec![];
+        let mut counter = 0u32;
+        while rs.len() < BLS_BATCH_SIZE {
+            let counter_bytes = [
+                builder.constant(counter & 0xff),
+                builder.constant(counter >> 8),
+            ];
+            let out = sha256(builder, &[seed.clone(), counter_bytes.to_vec()].concat());
+            for chunk in out.chunks(RANDOMIZER_BYTES) {
+                if rs.len() < BLS_BATCH_SIZE {
+                    let mut bits = vec![];
+                    for byte in chunk.iter().rev() {
+                        bits.extend(to_binary(builder, *byte, 8));
+                    }
+                    bits[RANDOMIZER_BYTES * 8 - 1] = one;
+                    rs.push(bits);
+                }
+            }
+            counter += 1;
+        }
+
+        let g1_gen = G1Affine::one(builder);
+        let mut ps = vec![g1.neg(builder, &g1_gen)];
+        let mut qs = vec![];
+        let mut r_sigs = vec![];
+        for i in 0..BLS_BATCH_SIZE {
+            r_sigs.push(g2.scalar_mul_bits(builder, &sigs[i], &rs[i]));
+            ps.push(g1.scalar_mul_bits(builder, &pub_keys[i], &rs[i]));
+            qs.push(G2Affine {
+                p: g2.hash_to_g2(builder, &self.msgs[i], BLS_BATCH_CIPHERSUITE),
+                lines: LineEvaluations::default(),
+            });
+        }
+        //the sigs are chosen by the prover, so their multiples are summed with guarded additions
+        let agg_sig = g2.sum(builder, &r_sigs);
+        qs.insert(
+            0,
+            G2Affine {
+                p: agg_sig,
+                lines: LineEvaluations::default(),
+            },
+        );
+
+        let f = pairing.miller_loop(builder, &ps, &mut qs).unwrap();
+        pairing.assert_final_exponentiation_is_one(builder, &f);
+
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.check_mul(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+// bls_batch_reference_assignment signs a distinct message under a distinct key in every slot
+pub fn bls_batch_reference_assignment() -> BLSBatchVerifyCircuit<M31> {
+    let mut assignment = BLSBatchVerifyCircuit::<M31> {
+        pub_keys: [[[M31::from(0); 48]; 2]; BLS_BATCH_SIZE],
+        msgs: [[M31::from(0x56); BLS_BATCH_MSG_LEN]; BLS_BATCH_SIZE],
+        sigs: [[[[M31::from(0); 48]; 2]; 2]; BLS_BATCH_SIZE],
+    };
+    let dst = BLS_BATCH_CIPHERSUITE.dst().unwrap();
+    for i in 0..BLS_BATCH_SIZE {
+        let sk = Fr::from(0x5eed + i as u64);
+        let mut msg = [0x56; BLS_BATCH_MSG_LEN];
+        msg[0] = i as u8;
+        let pk = (NativeG1::generator() * sk).into_affine();
+        let sig = (native::hash_to_g2(&msg, dst) * sk).into_affine();
+        native::g1_to_limbs(&pk, &mut assignment.pub_keys[i]);
+        native::g2_to_limbs(&sig, &mut assignment.sigs[i]);
+        assignment.msgs[i][0] = M31::from(i as u32);
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bls_batch_reference_assignment, BLSBatchVerifyCircuit};
+    use crate::bls12_381::register_bls12381_hint;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    #[test]
+    fn test_bls_batch_verify() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        debug_eval(
+            &BLSBatchVerifyCircuit::default(),
+            &bls_batch_reference_assignment(),
+            hint_registry,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bls_batch_verify_rejects_swapped_signatures() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        //the plain aggregate of the batch is unchanged, only the randomizers catch the swap
+        let mut assignment = bls_batch_reference_assignment();
+        assignment.sigs.swap(2, 9);
+        debug_eval(&BLSBatchVerifyCircuit::default(), &assignment, hint_registry);
+    }
+}
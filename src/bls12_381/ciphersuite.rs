@@ -0,0 +1,32 @@
+// Ciphersuite selects the domain separation tag used to hash messages to the curve. The
+// signature variants follow draft-irtf-cfrg-bls-signature 4.2: Ethereum signs under G2Pop,
+// Filecoin under G2Nul and drand quicknet under G1Nul.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ciphersuite {
+    // the fixed tag built into curve_f.hash_to_fp
+    CircuitStd,
+    G2Nul,
+    G2Pop,
+    // tag for proofs of possession of the G2-signature PoP scheme
+    G2PopProof,
+    G1Nul,
+    G1Pop,
+    G1PopProof,
+    Custom(&'static [u8]),
+}
+
+impl Ciphersuite {
+    // dst returns the tag, or None for CircuitStd whose tag is internal to circuit_std_rs
+    pub fn dst(&self) -> Option<&'static [u8]> {
+        match self {
+            Ciphersuite::CircuitStd => None,
+            Ciphersuite::G2Nul => Some(b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_"),
+            Ciphersuite::G2Pop => Some(b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_"),
+            Ciphersuite::G2PopProof => Some(b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_"),
+            Ciphersuite::G1Nul => Some(b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_"),
+            Ciphersuite::G1Pop => Some(b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_"),
+            Ciphersuite::G1PopProof => Some(b"BLS_POP_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_"),
+            Ciphersuite::Custom(dst) => Some(dst),
+        }
+    }
+}
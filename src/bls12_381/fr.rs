@@ -0,0 +1,408 @@
+use circuit_std_rs::big_int::*;
+use circuit_std_rs::gnark::element::*;
+use circuit_std_rs::gnark::emparam::FieldParams;
+use circuit_std_rs::gnark::field::GField;
+use circuit_std_rs::gnark::hints::unwrap_hint;
+use expander_compiler::{
+    declare_circuit,
+    frontend::{Config, Error, GenericDefine, M31Config, RootAPI, Variable, M31},
+};
+use num_bigint::BigInt;
+
+const MODULUS: &str =
+    "52435875175126190479447740508185965837690552500527637822603658699938581184513";
+
+// 2^256 mod r, used to fold the high half of a 512-bit hash
+const TWO_256_MOD_R: &str =
+    "10920338887063814464675503992315976177888879664585288394250266608035967270910";
+
+// r < 2^255, so a canonical scalar has 255 significant bits
+pub const FR_BITS: usize = 255;
+
+// x0^2 for the BLS parameter |x0| = 0xd201000000010000. r = x0^4 - x0^2 + 1, so every canonical
+// scalar is s1 + s2 * x0^2 with s1, s2 < x0^2 < 2^128.
+const X0_SQUARED: &str = "228988810152649578064853576960394133504";
+pub const FR_HALF_BITS: usize = 128;
+
+// Bls12381Fr emulates the scalar field of BLS12-381, the order r of G1 and G2. It uses the same
+// 32 x 8-bit layout as the other emulated fields, so scalars map straight to little-endian bytes.
+#[derive(Default, Clone, Copy)]
+pub struct Bls12381Fr {}
+
+impl FieldParams for Bls12381Fr {
+    fn nb_limbs() -> u32 {
+        32
+    }
+    fn bits_per_limb() -> u32 {
+        8
+    }
+    fn is_prime() -> bool {
+        true
+    }
+    fn modulus() -> BigInt {
+        MODULUS.parse().unwrap()
+    }
+}
+
+// ScalarF is the field chip for Bls12381Fr. Scalars that drive G1 and G2 multiplications are
+// reduced to their canonical residue first, then split into bits or windows.
+pub struct ScalarF {
+    pub field: GField<Bls12381Fr>,
+}
+
+impl ScalarF {
+    pub fn new<C: Config, B: RootAPI<C>>(native: &mut B) -> Self {
+        let field = GField::new(native, Bls12381Fr {});
+        Self { field }
+    }
+
+    // from_small returns the element of a native variable known to fit in 16 bits
+    pub fn from_small<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        v: Variable,
+    ) -> Element<Bls12381Fr> {
+        let bits = to_binary(native, v, 16);
+        let mut limbs = vec![
+            from_binary(native, bits[..8].to_vec()),
+            from_binary(native, bits[8..].to_vec()),
+        ];
+        limbs.resize(Bls12381Fr::nb_limbs() as usize, native.constant(0));
+        new_internal_element(limbs, 0)
+    }
+
+    // from_canonical_bytes returns the element of 32 little-endian bytes, which must encode an
+    // integer below r
+    pub fn from_canonical_bytes<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        bytes: &[Variable],
+    ) -> Element<Bls12381Fr> {
+        let s = new_internal_element::<Bls12381Fr>(bytes.to_vec(), 0);
+        self.assert_is_canonical(native, &s.limbs);
+        s
+    }
+
+    // reduce_wide returns k mod r for a 64-byte little-endian k, folding the high half with 2^256 mod r
+    pub fn reduce_wide<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        bytes: &[Variable],
+    ) -> Element<Bls12381Fr> {
+        let lo = new_internal_element::<Bls12381Fr>(bytes[..32].to_vec(), 0);
+        let hi = new_internal_element::<Bls12381Fr>(bytes[32..].to_vec(), 0);
+        let shift = value_of::<C, B, Bls12381Fr>(native, Box::new(TWO_256_MOD_R.to_string()));
+        let hi_shifted = self.field.mul(native, &hi, &shift);
+        let k = self.field.add(native, &lo, &hi_shifted);
+        self.reduce(native, &k)
+    }
+
+    pub fn add<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+        b: &Element<Bls12381Fr>,
+    ) -> Element<Bls12381Fr> {
+        self.field.add(native, a, b)
+    }
+
+    pub fn sub<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+        b: &Element<Bls12381Fr>,
+    ) -> Element<Bls12381Fr> {
+        self.field.sub(native, a, b)
+    }
+
+    pub fn mul<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+        b: &Element<Bls12381Fr>,
+    ) -> Element<Bls12381Fr> {
+        self.field.mul(native, a, b)
+    }
+
+    pub fn div<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+        b: &Element<Bls12381Fr>,
+    ) -> Element<Bls12381Fr> {
+        self.field.div(native, a, b)
+    }
+
+    pub fn neg<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+    ) -> Element<Bls12381Fr> {
+        self.field.neg(native, a)
+    }
+
+    pub fn inverse<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+    ) -> Element<Bls12381Fr> {
+        self.field.inverse(native, a)
+    }
+
+    // select returns a if selector is 1 and b otherwise
+    pub fn select<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        selector: Variable,
+        a: &Element<Bls12381Fr>,
+        b: &Element<Bls12381Fr>,
+    ) -> Element<Bls12381Fr> {
+        self.field.select(native, selector, a, b)
+    }
+
+    pub fn is_zero<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+    ) -> Variable {
+        self.field.is_zero(native, a)
+    }
+
+    pub fn assert_is_equal<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+        b: &Element<Bls12381Fr>,
+    ) {
+        self.field.assert_is_equal(native, a, b)
+    }
+
+    // assert_is_canonical checks that little-endian byte limbs encode an integer below r
+    pub fn assert_is_canonical<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        limbs: &Vec<Variable>,
+    ) {
+        let r = value_of::<C, B, Bls12381Fr>(native, Box::new(Bls12381Fr::modulus()));
+        let is_reduced = big_less_than(
+            native,
+            Bls12381Fr::bits_per_limb() as usize,
+            Bls12381Fr::nb_limbs() as usize,
+            limbs,
+            &r.limbs,
+        );
+        let one = native.constant(1);
+        native.assert_is_equal(is_reduced, one);
+    }
+
+    // reduce returns the canonical residue of a mod r, pinned by equality mod r and the bound
+    pub fn reduce<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Bls12381Fr>,
+    ) -> Element<Bls12381Fr> {
+        let outputs = self
+            .field
+            .new_hint(native, "myhint.bls12381frreducehint", 1, vec![a.my_clone()]);
+        let s = outputs[0].clone();
+        self.field.assert_is_equal(native, &s, a);
+        self.assert_is_canonical(native, &s.limbs);
+        s
+    }
+
+    // to_bits returns the FR_BITS little-endian bits of a canonical scalar
+    pub fn to_bits<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        s: &Element<Bls12381Fr>,
+    ) -> Vec<Variable> {
+        let mut bits = vec![];
+        for limb in s.limbs.iter() {
+            bits.extend(to_binary(native, *limb, 8));
+        }
+        bits.truncate(FR_BITS);
+        bits
+    }
+
+    // to_windows splits a canonical scalar into little-endian windows of window_bits bits, the
+    // digits of a fixed-window scalar multiplication. The last window may be shorter.
+    pub fn to_windows<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        s: &Element<Bls12381Fr>,
+        window_bits: usize,
+    ) -> Vec<Variable> {
+        let bits = self.to_bits(native, s);
+        bits.chunks(window_bits)
+            .map(|window| from_binary(native, window.to_vec()))
+            .collect()
+    }
+
+    // decompose_glv splits a canonical s into s = s1 + s2 * x0^2 and returns the FR_HALF_BITS
+    // little-endian bits of s1 and s2. x0^2 is an eigenvalue of an endomorphism on both G1 and G2,
+    // see G1::scalar_mul_glv and G2::scalar_mul_gls. The halves come from a hint; bounding their
+    // limbs and checking the sum mod r pins them down as far as any r-torsion point can tell.
+    pub fn decompose_glv<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        s: &Element<Bls12381Fr>,
+    ) -> (Vec<Variable>, Vec<Variable>) {
+        let halves = self
+            .field
+            .new_hint(native, "myhint.bls12381frglvhint", 2, vec![s.my_clone()]);
+        let mut bits = vec![];
+        for half in halves.iter() {
+            let mut half_bits = vec![];
+            for limb in half.limbs[..FR_HALF_BITS / 8].iter() {
+                half_bits.extend(to_binary(native, *limb, 8));
+            }
+            for limb in half.limbs[FR_HALF_BITS / 8..].iter() {
+                native.assert_is_zero(*limb);
+            }
+            bits.push(half_bits);
+        }
+        let x0_squared = value_of::<C, B, Bls12381Fr>(native, Box::new(X0_SQUARED.to_string()));
+        let high = self.field.mul(native, &halves[1], &x0_squared);
+        let sum = self.field.add(native, &halves[0], &high);
+        self.field.assert_is_equal(native, &sum, s);
+        let s2_bits = bits.pop().unwrap();
+        let s1_bits = bits.pop().unwrap();
+        (s1_bits, s2_bits)
+    }
+
+    pub fn final_check<C: Config, B: RootAPI<C>>(&mut self, native: &mut B) {
+        self.field.check_mul(native);
+        self.field.table.final_check(native);
+        self.field.table.final_check(native);
+        self.field.table.final_check(native);
+    }
+}
+
+pub fn fr_reduce_hint(inputs: &[M31], outputs: &mut [M31]) -> Result<(), Error> {
+    if let Err(err) = unwrap_hint(true, true, inputs, outputs, |inputs| {
+        Ok(vec![&inputs[0] % Bls12381Fr::modulus()])
+    }) {
+        panic!("fr_reduce_hint: {}", err);
+    }
+    Ok(())
+}
+
+pub fn fr_glv_hint(inputs: &[M31], outputs: &mut [M31]) -> Result<(), Error> {
+    if let Err(err) = unwrap_hint(true, true, inputs, outputs, |inputs| {
+        let x0_squared = X0_SQUARED.parse::<BigInt>().unwrap();
+        Ok(vec![&inputs[0] % &x0_squared, &inputs[0] / &x0_squared])
+    }) {
+        panic!("fr_glv_hint: {}", err);
+    }
+    Ok(())
+}
+
+declare_circuit!(FrArithCircuit {
+    a: [Variable; 32],
+    b: [Variable; 32],
+    // a / b mod r and its 4-bit windows
+    quotient: [Variable; 32],
+    quotient_windows: [Variable; 64],
+    wide: [Variable; 64],
+    wide_reduced: [Variable; 32],
+});
+
+impl GenericDefine<M31Config> for FrArithCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut scalar_f = ScalarF::new(builder);
+        let a = scalar_f.from_canonical_bytes(builder, &self.a);
+        let b = scalar_f.from_canonical_bytes(builder, &self.b);
+
+        let a_neg = scalar_f.neg(builder, &a);
+        let zero = scalar_f.add(builder, &a, &a_neg);
+        let is_zero = scalar_f.is_zero(builder, &zero);
+        let one = builder.constant(1);
+        builder.assert_is_equal(is_zero, one);
+
+        let b_inv = scalar_f.inverse(builder, &b);
+        let quotient = scalar_f.mul(builder, &a, &b_inv);
+        let quotient = scalar_f.reduce(builder, &quotient);
+        let expected = scalar_f.from_canonical_bytes(builder, &self.quotient);
+        scalar_f.assert_is_equal(builder, &quotient, &expected);
+        let windows = scalar_f.to_windows(builder, &quotient, 4);
+        for (window, expected) in windows.iter().zip(self.quotient_windows.iter()) {
+            builder.assert_is_equal(*window, *expected);
+        }
+
+        let wide = scalar_f.reduce_wide(builder, &self.wide);
+        let expected = scalar_f.from_canonical_bytes(builder, &self.wide_reduced);
+        scalar_f.assert_is_equal(builder, &wide, &expected);
+
+        scalar_f.final_check(builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bls12381Fr, FrArithCircuit};
+    use crate::bls12_381::register_bls12381_hint;
+    use circuit_std_rs::gnark::emparam::FieldParams;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+    use num_bigint::BigInt;
+
+    fn to_limbs(v: &BigInt, limbs: &mut [M31]) {
+        let (_, bytes) = v.to_bytes_le();
+        for (limb, byte) in limbs.iter_mut().zip(bytes.iter()) {
+            *limb = M31::from(*byte as u32);
+        }
+    }
+
+    fn fr_assignment() -> FrArithCircuit<M31> {
+        let r = Bls12381Fr::modulus();
+        let a = &r / 7u32 + 0x5eedu32;
+        let b = &r - 3u32;
+        let quotient = &a * b.modpow(&(&r - 2u32), &r) % &r;
+        let wide_bytes = (0..64).map(|i| (i * 37 + 11) as u8).collect::<Vec<_>>();
+        let wide = BigInt::from_bytes_le(num_bigint::Sign::Plus, &wide_bytes);
+
+        let mut assignment = FrArithCircuit::<M31> {
+            a: [M31::from(0); 32],
+            b: [M31::from(0); 32],
+            quotient: [M31::from(0); 32],
+            quotient_windows: [M31::from(0); 64],
+            wide: [M31::from(0); 64],
+            wide_reduced: [M31::from(0); 32],
+        };
+        to_limbs(&a, &mut assignment.a);
+        to_limbs(&b, &mut assignment.b);
+        to_limbs(&quotient, &mut assignment.quotient);
+        for (i, window) in assignment.quotient_windows.iter_mut().enumerate() {
+            let digit = (&quotient >> (4 * i)) & BigInt::from(0xf);
+            *window = M31::from(u32::try_from(digit).unwrap());
+        }
+        for (limb, byte) in assignment.wide.iter_mut().zip(wide_bytes.iter()) {
+            *limb = M31::from(*byte as u32);
+        }
+        to_limbs(&(wide % &r), &mut assignment.wide_reduced);
+        assignment
+    }
+
+    #[test]
+    fn test_fr_arith() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        debug_eval(&FrArithCircuit::default(), &fr_assignment(), hint_registry);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fr_arith_rejects_non_canonical_input() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        //b + r encodes the same residue but is not below r
+        let mut assignment = fr_assignment();
+        let b = Bls12381Fr::modulus() * 2u32 - 3u32;
+        to_limbs(&b, &mut assignment.b);
+        debug_eval(&FrArithCircuit::default(), &assignment, hint_registry);
+    }
+}
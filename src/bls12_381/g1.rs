@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use ark_bls12_381::G1Affine as NativeG1;
 use circuit_std_rs::big_int::*;
 use circuit_std_rs::gnark::element::*;
 use circuit_std_rs::gnark::emparam::Bls12381Fp;
@@ -11,9 +12,17 @@ use expander_compiler::{
 };
 use num_bigint::BigInt;
 
+use crate::bls12_381::ciphersuite::Ciphersuite;
+use crate::bls12_381::fr::{Bls12381Fr, ScalarF};
+use crate::bls12_381::hash_to_field::{hash_to_field, hash_to_field_var};
+use crate::bls12_381::native::{fq_to_bigint, g1_scalar_mul_offset};
+
 const M_COMPRESSED_SMALLEST: u8 = 0b100 << 5;
 const M_COMPRESSED_LARGEST: u8 = 0b101 << 5;
 
+// window width of G1::scalar_mul_var and G2::scalar_mul_var
+pub const SCALAR_MUL_WINDOW: usize = 4;
+
 #[derive(Default, Clone)]
 pub struct G1Affine {
     pub x: Element<Bls12381Fp>,
@@ -37,6 +46,13 @@ impl G1Affine {
             y: value_of::<C, B, Bls12381Fp>(native, Box::new("1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569".to_string())),
         }
     }
+    // from_native returns a point fixed at circuit-build time
+    pub fn from_native<C: Config, B: RootAPI<C>>(native: &mut B, p: &NativeG1) -> Self {
+        Self {
+            x: value_of::<C, B, Bls12381Fp>(native, Box::new(fq_to_bigint(&p.x))),
+            y: value_of::<C, B, Bls12381Fp>(native, Box::new(fq_to_bigint(&p.y))),
+        }
+    }
 }
 pub struct G1 {
     pub curve_f: CurveF,
@@ -103,6 +119,259 @@ impl G1 {
         self.curve_f.assert_is_equal(native, &a.x, &b.x);
         self.curve_f.assert_is_equal(native, &a.y, &b.y);
     }
+    // select returns p if selector is 1 and q otherwise
+    pub fn select<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        selector: Variable,
+        p: &G1Affine,
+        q: &G1Affine,
+    ) -> G1Affine {
+        let x = self.curve_f.select(native, selector, &p.x, &q.x);
+        let y = self.curve_f.select(native, selector, &p.y, &q.y);
+        G1Affine::new(x, y)
+    }
+    // scalar_mul_bits computes [s]p for s given as little-endian bits whose top bit is 1. The
+    // accumulator then starts at p and is [2k]p with 0 < 2k < 2^bits.len() before each addition
+    // of p, so for fewer than 254 bits the incomplete formulas never see equal or opposite points.
+    pub fn scalar_mul_bits<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &G1Affine,
+        bits: &[Variable],
+    ) -> G1Affine {
+        let top = bits[bits.len() - 1];
+        let one = native.constant(1);
+        native.assert_is_equal(top, one);
+        let mut res = p.clone();
+        for bit in bits.iter().rev().skip(1) {
+            res = self.double(native, &res);
+            let sum = self.add(native, &res, p);
+            res = self.select(native, *bit, &sum, &res);
+        }
+        res
+    }
+    // scalar_mul_var computes [s]p for p in G1 and any s != 0 mod r given as little-endian bits,
+    // adding one looked-up multiple of p per SCALAR_MUL_WINDOW-bit window. The accumulator starts
+    // at the point A of g1_scalar_mul_offset and holds [2^k]A + [t]p, which is never in G1, so it
+    // never shares an x-coordinate with a table entry. E(Fp) has odd order, so no doubling sees
+    // y = 0 either. The additions are guarded all the same, which only rejects s = 0 mod r, where
+    // the final subtraction of [2^k]A would have to produce the point at infinity.
+    pub fn scalar_mul_var<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &G1Affine,
+        bits: &[Variable],
+    ) -> G1Affine {
+        let table = self.multiples(native, p, 1 << SCALAR_MUL_WINDOW);
+        let windows = bits
+            .chunks(SCALAR_MUL_WINDOW)
+            .map(|window| window.to_vec())
+            .collect::<Vec<_>>();
+        self.windowed_sum(native, &[table], &[windows], SCALAR_MUL_WINDOW)
+    }
+    // scalar_mul_glv computes [s]p for p in G1 and a canonical s != 0 mod r. phi acts on G1 as
+    // [x0^2 - 1], a cube root of unity mod r, so phi^2(p) = [-x0^2]p. With s = s1 + s2 * x0^2 from
+    // ScalarF::decompose_glv and q = -phi^2(p), [s]p = [s1]p + [s2]q.
+    // Each window takes two bits of both 128-bit halves and adds [i]p + [j]q from a joint table,
+    // so the loop has half the doublings of scalar_mul_var for the same number of additions.
+    pub fn scalar_mul_glv<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        scalar_f: &mut ScalarF,
+        p: &G1Affine,
+        s: &Element<Bls12381Fr>,
+    ) -> G1Affine {
+        let (s1_bits, s2_bits) = scalar_f.decompose_glv(native, s);
+        let phi_p = self.phi(native, p);
+        let phi2_p = self.phi(native, &phi_p);
+        let q = self.neg(native, &phi2_p);
+
+        let half = SCALAR_MUL_WINDOW / 2;
+        let p_multiples = self.multiples(native, p, 1 << half);
+        let q_multiples = self.multiples(native, &q, 1 << half);
+        let mut table = vec![];
+        for (j, q_j) in q_multiples.iter().enumerate() {
+            for (i, p_i) in p_multiples.iter().enumerate() {
+                table.push(match (i, j) {
+                    (_, 0) => p_i.clone(),
+                    (0, _) => q_j.clone(),
+                    _ => self.add_unequal(native, p_i, q_j),
+                });
+            }
+        }
+        let windows = s1_bits
+            .chunks(half)
+            .zip(s2_bits.chunks(half))
+            .map(|(s1, s2)| [s1, s2].concat())
+            .collect::<Vec<_>>();
+        self.windowed_sum(native, &[table], &[windows], half)
+    }
+    // msm computes sum [s_i]p_i for points p_i in G1 and scalars given as little-endian bits of
+    // equal length, rejecting a sum of 0. It is Straus' method: every point gets the window table
+    // of scalar_mul_var and all of them add into one offset accumulator, so each window costs
+    // SCALAR_MUL_WINDOW doublings in total instead of per point. For n 255-bit scalars, counting
+    // group operations, each a handful of emulated Fp multiplications:
+    //
+    //                                 doublings     additions
+    //   n x mul_windowed (2-bit)      255n          129n, 97n on average as zero digits are skipped
+    //   n x scalar_mul_var (4-bit)    253n          78n
+    //   msm                           252 + n       77n + 1
+    //
+    // so for the 512 keys of a sync committee msm drops about 129k doublings. The additions stay
+    // linear; a bucket method would only beat that with more points than a circuit here holds.
+    pub fn msm<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        points: &[G1Affine],
+        scalars: &[Vec<Variable>],
+    ) -> G1Affine {
+        assert_eq!(points.len(), scalars.len());
+        let mut tables = vec![];
+        let mut windows = vec![];
+        for (p, bits) in points.iter().zip(scalars.iter()) {
+            tables.push(self.multiples(native, p, 1 << SCALAR_MUL_WINDOW));
+            windows.push(
+                bits.chunks(SCALAR_MUL_WINDOW)
+                    .map(|window| window.to_vec())
+                    .collect::<Vec<_>>(),
+            );
+        }
+        self.windowed_sum(native, &tables, &windows, SCALAR_MUL_WINDOW)
+    }
+    // multiples returns [j]p for j < n, with p standing in for [0]p since zero digits discard
+    // their sum
+    fn multiples<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &G1Affine,
+        n: usize,
+    ) -> Vec<G1Affine> {
+        let mut table = vec![p.clone(), p.clone(), self.double(native, p)];
+        for j in 3..n {
+            let entry = self.add_unequal(native, &table[j - 1], p);
+            table.push(entry);
+        }
+        table
+    }
+    // windowed_sum returns sum_t sum_i [2^(i * doublings)] tables[t][windows[t][i]], running the
+    // offset accumulator of scalar_mul_var from the most significant window down. Every table
+    // adds into the same accumulator, so the doublings are shared between them.
+    fn windowed_sum<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        tables: &[Vec<G1Affine>],
+        windows: &[Vec<Vec<Variable>>],
+        doublings: usize,
+    ) -> G1Affine {
+        let len = windows[0].len();
+        assert!(windows.iter().all(|w| w.len() == len), "scalars must have the same length");
+        let (start, end) = g1_scalar_mul_offset((len - 1) * doublings);
+        let mut acc = G1Affine::from_native(native, &start);
+        for i in (0..len).rev() {
+            if i + 1 < len {
+                for _ in 0..doublings {
+                    acc = self.double(native, &acc);
+                }
+            }
+            for (table, windows) in tables.iter().zip(windows.iter()) {
+                let entry = self.lookup(native, table, &windows[i]);
+                let sum = self.add_unequal(native, &acc, &entry);
+                let digit = from_binary(native, windows[i].to_vec());
+                let is_zero = native.is_zero(digit);
+                acc = self.select(native, is_zero, &acc, &sum);
+            }
+        }
+        let end = G1Affine::from_native(native, &end);
+        let end = self.neg(native, &end);
+        self.add_unequal(native, &acc, &end)
+    }
+    // lookup returns table[j] for j given as little-endian bits, folding the table bit by bit
+    fn lookup<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        table: &[G1Affine],
+        bits: &[Variable],
+    ) -> G1Affine {
+        let mut layer = table[..1 << bits.len()].to_vec();
+        for bit in bits.iter() {
+            layer = layer
+                .chunks(2)
+                .map(|pair| self.select(native, *bit, &pair[1], &pair[0]))
+                .collect();
+        }
+        layer.pop().unwrap()
+    }
+    // add_unequal is add with the incomplete case ruled out: if p.x == q.x the slope constraint
+    // degenerates to 0 == 0 and the prover could pick any λ, so the x-coordinates must differ.
+    pub fn add_unequal<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &G1Affine,
+        q: &G1Affine,
+    ) -> G1Affine {
+        let qxpx = self.curve_f.sub(native, &q.x, &p.x);
+        let same_x = self.curve_f.is_zero(native, &qxpx);
+        native.assert_is_zero(same_x);
+        self.add(native, p, q)
+    }
+    // sum_selected returns the sum of the points[i] with bits[i] == 1, for points in the subgroup.
+    // The accumulator starts at the offset A of scalar_mul_var, which lies outside the subgroup,
+    // so A plus any sum of points is never +-points[i] and add_unequal holds even for repeated
+    // points. The final subtraction of A fails if the selected points sum to zero.
+    pub fn sum_selected<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        points: &[G1Affine],
+        bits: &[Variable],
+    ) -> G1Affine {
+        assert_eq!(points.len(), bits.len());
+        let (offset, _) = g1_scalar_mul_offset(0);
+        let offset = G1Affine::from_native(native, &offset);
+        let mut acc = offset.clone();
+        for (p, bit) in points.iter().zip(bits.iter()) {
+            let sum = self.add_unequal(native, &acc, p);
+            acc = self.select(native, *bit, &sum, &acc);
+        }
+        let offset = self.neg(native, &offset);
+        self.add_unequal(native, &acc, &offset)
+    }
+    // sum is sum_selected with every point selected
+    pub fn sum<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        points: &[G1Affine],
+    ) -> G1Affine {
+        let one = native.constant(1);
+        self.sum_selected(native, points, &vec![one; points.len()])
+    }
+    pub fn assert_on_curve<C: Config, B: RootAPI<C>>(&mut self, native: &mut B, p: &G1Affine) {
+        //y^2 = x^3 + 4
+        let xxx = self.curve_f.mul(native, &p.x, &p.x);
+        let xxx = self.curve_f.mul(native, &xxx, &p.x);
+        let b_curve_coeff = value_of::<C, B, Bls12381Fp>(native, Box::new(4));
+        let rhs = self.curve_f.add(native, &xxx, &b_curve_coeff);
+        let yy = self.curve_f.mul(native, &p.y, &p.y);
+        self.curve_f.assert_is_equal(native, &yy, &rhs);
+    }
+    // phi is the GLV endomorphism (x, y) -> (w*x, y), where w is a primitive cube root of unity
+    pub fn phi<C: Config, B: RootAPI<C>>(&mut self, native: &mut B, p: &G1Affine) -> G1Affine {
+        let x = self.curve_f.mul(native, &p.x, &self.w);
+        G1Affine::new(x, p.y.my_clone())
+    }
+    // assert_in_subgroup checks that an on-curve point p lies in the r-torsion, using
+    // [r]p == 0 <==> phi(p) == [x0^2 - 1]p, i.e. [x0^2]phi(p) == -p (Scott, ePrint 2021/1130).
+    // The additions are guarded, so points with a small-order component cannot slip through
+    // a degenerate addition.
+    pub fn assert_in_subgroup<C: Config, B: RootAPI<C>>(&mut self, native: &mut B, p: &G1Affine) {
+        let x_big = BigInt::from_str("15132376222941642752").expect("Invalid string for BigInt");
+
+        let phi_p = self.phi(native, p);
+        let res = self.mul_windowed_with(native, &phi_p, x_big.clone(), true);
+        let res = self.mul_windowed_with(native, &res, x_big, true);
+        let p_neg = self.neg(native, p);
+        self.assert_is_equal(native, &res, &p_neg);
+    }
     pub fn uncompressed<C: Config, B: RootAPI<C>>(
         &mut self,
         native: &mut B,
@@ -170,8 +439,9 @@ impl G1 {
 
         let y = self.curve_f.select(native, neg_flag, &neg_y, &y);
 
-        //TBD: subgroup check, do we need to do that? Since we are pretty sure that the public key bytes are correct, its unmashalling must be on the right curve
-        G1Affine { x: px, y }
+        let res = G1Affine { x: px, y };
+        self.assert_in_subgroup(native, &res);
+        res
     }
     pub fn hash_to_fp<C: Config, B: RootAPI<C>>(
         &mut self,
@@ -181,6 +451,16 @@ impl G1 {
         let u = self.curve_f.hash_to_fp(native, data, 2);
         (u[0].clone(), u[1].clone())
     }
+    // hash_to_fp_with_dst is hash_to_fp under an explicit domain separation tag
+    pub fn hash_to_fp_with_dst<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        data: &[Variable],
+        dst: &[u8],
+    ) -> (Element<Bls12381Fp>, Element<Bls12381Fp>) {
+        let u = hash_to_field(native, &mut self.curve_f, data, dst, 2);
+        (u[0].clone(), u[1].clone())
+    }
     pub fn g1_isogeny<C: Config, B: RootAPI<C>>(
         &mut self,
         native: &mut B,
@@ -310,6 +590,34 @@ impl G1 {
         }
         dst
     }
+    // hash_to_g1 is hash_to_curve of RFC 9380 under the tag of suite
+    pub fn hash_to_g1<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        msg: &[Variable],
+        suite: Ciphersuite,
+    ) -> G1Affine {
+        let (hm0, hm1) = match suite.dst() {
+            Some(dst) => self.hash_to_fp_with_dst(native, msg, dst),
+            None => self.hash_to_fp(native, msg),
+        };
+        self.map_to_g1(native, &hm0, &hm1)
+    }
+    // hash_to_g1_var is hash_to_g1 over the first msg_len bytes of msg. The tag of
+    // curve_f.hash_to_fp is not available here, so suite must carry its own tag.
+    pub fn hash_to_g1_var<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        msg: &[Variable],
+        msg_len: Variable,
+        suite: Ciphersuite,
+    ) -> G1Affine {
+        let dst = suite
+            .dst()
+            .expect("variable-length hashing needs a ciphersuite with an explicit tag");
+        let u = hash_to_field_var(native, &mut self.curve_f, msg, msg_len, dst, 2);
+        self.map_to_g1(native, &u[0], &u[1])
+    }
     pub fn map_to_g1<C: Config, B: RootAPI<C>>(
         &mut self,
         native: &mut B,
@@ -327,9 +635,18 @@ impl G1 {
         native: &mut B,
         q: &G1Affine,
         s: BigInt,
+    ) -> G1Affine {
+        self.mul_windowed_with(native, q, s, false)
+    }
+    fn mul_windowed_with<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        q: &G1Affine,
+        s: BigInt,
+        guarded: bool,
     ) -> G1Affine {
         let double_q = self.double(native, q);
-        let triple_q = self.add(native, &double_q, q);
+        let triple_q = self.add_maybe_unequal(native, &double_q, q, guarded);
         let ops = vec![q.clone(), double_q, triple_q];
 
         let b = s.to_bytes_be();
@@ -338,14 +655,14 @@ impl G1 {
 
         res = self.double(native, &res);
         res = self.double(native, &res);
-        res = self.add(native, &res, &ops[0]);
+        res = self.add_maybe_unequal(native, &res, &ops[0], guarded);
 
         res = self.double(native, &res);
         res = self.double(native, &res);
 
         res = self.double(native, &res);
         res = self.double(native, &res);
-        res = self.add(native, &res, &ops[1]);
+        res = self.add_maybe_unequal(native, &res, &ops[1], guarded);
 
         for w in b {
             let mut mask = 0xc0;
@@ -354,13 +671,26 @@ impl G1 {
                 res = self.double(native, &res);
                 let c = (w & mask) >> (6 - 2 * j);
                 if c != 0 {
-                    res = self.add(native, &res, &ops[(c - 1) as usize]);
+                    res = self.add_maybe_unequal(native, &res, &ops[(c - 1) as usize], guarded);
                 }
                 mask >>= 2;
             }
         }
         res
     }
+    fn add_maybe_unequal<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &G1Affine,
+        q: &G1Affine,
+        guarded: bool,
+    ) -> G1Affine {
+        if guarded {
+            self.add_unequal(native, p, q)
+        } else {
+            self.add(native, p, q)
+        }
+    }
     pub fn clear_cofactor<C: Config, B: RootAPI<C>>(
         &mut self,
         native: &mut B,
@@ -517,6 +847,23 @@ impl GenericDefine<M31Config> for G1UncompressCircuit<Variable> {
     }
 }
 
+declare_circuit!(G1SubgroupCheckCircuit {
+    p: [[Variable; 48]; 2],
+});
+
+impl GenericDefine<M31Config> for G1SubgroupCheckCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut g1 = G1::new(builder);
+        let p = G1Affine::from_vars(self.p[0].to_vec(), self.p[1].to_vec());
+        g1.assert_on_curve(builder, &p);
+        g1.assert_in_subgroup(builder, &p);
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+    }
+}
+
 declare_circuit!(HashToG1Circuit {
     msg: [Variable; 32],
     out: [[Variable; 48]; 2],
@@ -536,12 +883,97 @@ impl GenericDefine<M31Config> for HashToG1Circuit<Variable> {
     }
 }
 
+// IETF hash_to_curve test vectors use their own tag, RFC 9380 J.9.1
+declare_circuit!(HashToG1QuuxCircuit {
+    msg: [Variable; 3],
+    out: [[Variable; 48]; 2],
+});
+
+impl GenericDefine<M31Config> for HashToG1QuuxCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut g1 = G1::new(builder);
+        let suite = Ciphersuite::Custom(b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_");
+        let res = g1.hash_to_g1(builder, &self.msg, suite);
+        let target_out = G1Affine::from_vars(self.out[0].to_vec(), self.out[1].to_vec());
+        g1.assert_is_equal(builder, &res, &target_out);
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+    }
+}
+
+declare_circuit!(G1ScalarMulCircuit {
+    p: [[Variable; 48]; 2],
+    s: [Variable; 32],
+    r: [[Variable; 48]; 2],
+});
+
+impl GenericDefine<M31Config> for G1ScalarMulCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut g1 = G1::new(builder);
+        let mut scalar_f = ScalarF::new(builder);
+        let p = G1Affine::from_vars(self.p[0].to_vec(), self.p[1].to_vec());
+        let s = scalar_f.from_canonical_bytes(builder, &self.s);
+        let bits = scalar_f.to_bits(builder, &s);
+        let res = g1.scalar_mul_var(builder, &p, &bits);
+        let expected = G1Affine::from_vars(self.r[0].to_vec(), self.r[1].to_vec());
+        g1.assert_is_equal(builder, &res, &expected);
+        let res = g1.scalar_mul_glv(builder, &mut scalar_f, &p, &s);
+        g1.assert_is_equal(builder, &res, &expected);
+        scalar_f.final_check(builder);
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+    }
+}
+
+pub const G1_MSM_SIZE: usize = 3;
+
+declare_circuit!(G1MsmCircuit {
+    points: [[[Variable; 48]; 2]; G1_MSM_SIZE],
+    scalars: [[Variable; 32]; G1_MSM_SIZE],
+    r: [[Variable; 48]; 2],
+});
+
+impl GenericDefine<M31Config> for G1MsmCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut g1 = G1::new(builder);
+        let mut scalar_f = ScalarF::new(builder);
+        let mut points = vec![];
+        let mut scalars = vec![];
+        for (p, s) in self.points.iter().zip(self.scalars.iter()) {
+            points.push(G1Affine::from_vars(p[0].to_vec(), p[1].to_vec()));
+            let s = scalar_f.from_canonical_bytes(builder, s);
+            scalars.push(scalar_f.to_bits(builder, &s));
+        }
+        let res = g1.msm(builder, &points, &scalars);
+        let expected = G1Affine::from_vars(self.r[0].to_vec(), self.r[1].to_vec());
+        g1.assert_is_equal(builder, &res, &expected);
+        scalar_f.final_check(builder);
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::G1AddCircuit;
+    use super::G1SubgroupCheckCircuit;
     use super::G1UncompressCircuit;
     // use super::MapToG1Circuit;
     use super::HashToG1Circuit;
+    use super::HashToG1QuuxCircuit;
+    use super::G1ScalarMulCircuit;
+    use super::{G1MsmCircuit, G1_MSM_SIZE};
+    use crate::bls12_381::native::g1_to_limbs;
+    use crate::bls12_381::register_bls12381_hint;
+    use ark_bls12_381::{Fr, G1Affine as NativeG1, G1Projective};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{BigInteger, PrimeField, Zero};
     use circuit_std_rs::utils::register_hint;
     use expander_compiler::frontend::*;
     use expander_compiler::{
@@ -633,6 +1065,44 @@ mod tests {
         debug_eval(&G1UncompressCircuit::default(), &assignment, hint_registry);
     }
 
+    #[test]
+    fn test_g1_subgroup_check() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        let mut assignment = G1SubgroupCheckCircuit::<M31> {
+            p: [[M31::default(); 48]; 2],
+        };
+        let x_bigint = BigInt::from_str_radix("956996561804650125715590823042978408716123343953697897618645235063950952926609558156980737775438019700668816652798", 10).unwrap();
+        let y_bigint = BigInt::from_str_radix("3556009343530533802204184826723274316816769528634825602353881354158551671080148026501040863742187196667680827782849", 10).unwrap();
+        let x_bytes = x_bigint.to_bytes_le();
+        let y_bytes = y_bigint.to_bytes_le();
+        for i in 0..48 {
+            assignment.p[0][i] = M31::from(x_bytes.1[i] as u32);
+            assignment.p[1][i] = M31::from(y_bytes.1[i] as u32);
+        }
+
+        debug_eval(&G1SubgroupCheckCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_g1_subgroup_check_rejects_cofactor_point() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        let mut assignment = G1SubgroupCheckCircuit::<M31> {
+            p: [[M31::default(); 48]; 2],
+        };
+        // (5, sqrt(5^3 + 4)) is on the curve but not in G1
+        let y_bigint = BigInt::from_str_radix("2037214210573583170464691611659423517269065329685174421529737234252314188159418646987978679703439991468731867518477", 10).unwrap();
+        let y_bytes = y_bigint.to_bytes_le();
+        assignment.p[0][0] = M31::from(5);
+        for i in 0..48 {
+            assignment.p[1][i] = M31::from(y_bytes.1[i] as u32);
+        }
+
+        debug_eval(&G1SubgroupCheckCircuit::default(), &assignment, hint_registry);
+    }
+
     #[test]
     fn test_hash_to_g1() {
         // compile_generic(&HashToG2Circuit::default(), CompileOptions::default()).unwrap();
@@ -665,4 +1135,101 @@ mod tests {
 
         debug_eval(&HashToG1Circuit::default(), &assignment, hint_registry);
     }
+
+    #[test]
+    fn test_hash_to_g1_with_custom_dst() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let mut assignment = HashToG1QuuxCircuit::<M31> {
+            msg: [M31::from(0); 3],
+            out: [[M31::from(0); 48]; 2],
+        };
+        let x_bigint = BigInt::from_str_radix("03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903", 16).unwrap();
+        let y_bigint = BigInt::from_str_radix("0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d", 16).unwrap();
+        let x_bytes = x_bigint.to_bytes_le();
+        let y_bytes = y_bigint.to_bytes_le();
+
+        for (i, byte) in b"abc".iter().enumerate() {
+            assignment.msg[i] = M31::from(*byte as u32);
+        }
+        for i in 0..48 {
+            assignment.out[0][i] = M31::from(x_bytes.1[i] as u32);
+            assignment.out[1][i] = M31::from(y_bytes.1[i] as u32);
+        }
+
+        debug_eval(&HashToG1QuuxCircuit::default(), &assignment, hint_registry);
+    }
+
+    fn g1_scalar_mul_assignment(s: Fr) -> G1ScalarMulCircuit<M31> {
+        let p = (NativeG1::generator() * Fr::from(0x5eed)).into_affine();
+        let mut assignment = G1ScalarMulCircuit::<M31> {
+            p: [[M31::from(0); 48]; 2],
+            s: [M31::from(0); 32],
+            r: [[M31::from(0); 48]; 2],
+        };
+        g1_to_limbs(&p, &mut assignment.p);
+        for (limb, byte) in assignment.s.iter_mut().zip(s.into_bigint().to_bytes_le()) {
+            *limb = M31::from(byte as u32);
+        }
+        if !s.is_zero() {
+            g1_to_limbs(&(p * s).into_affine(), &mut assignment.r);
+        }
+        assignment
+    }
+
+    #[test]
+    fn test_g1_scalar_mul_var() {
+        //r - 1, a scalar with zero windows at the top and in the middle, and 1
+        let scalars = [-Fr::from(1), Fr::from(0x1000_0000_0000_0030u64), Fr::from(1)];
+        for s in scalars {
+            let mut hint_registry = HintRegistry::<M31>::new();
+            register_hint(&mut hint_registry);
+            register_bls12381_hint(&mut hint_registry);
+            debug_eval(
+                &G1ScalarMulCircuit::default(),
+                &g1_scalar_mul_assignment(s),
+                hint_registry,
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_g1_scalar_mul_var_rejects_zero() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        debug_eval(
+            &G1ScalarMulCircuit::default(),
+            &g1_scalar_mul_assignment(Fr::from(0)),
+            hint_registry,
+        );
+    }
+
+    #[test]
+    fn test_g1_msm() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let scalars = [-Fr::from(1), Fr::from(0x1000_0000_0000_0030u64), Fr::from(1)];
+        let mut assignment = G1MsmCircuit::<M31> {
+            points: [[[M31::from(0); 48]; 2]; G1_MSM_SIZE],
+            scalars: [[M31::from(0); 32]; G1_MSM_SIZE],
+            r: [[M31::from(0); 48]; 2],
+        };
+        let mut sum = G1Projective::zero();
+        for (i, s) in scalars.iter().enumerate() {
+            let p = (NativeG1::generator() * Fr::from(0x5eed + i as u64)).into_affine();
+            g1_to_limbs(&p, &mut assignment.points[i]);
+            let bytes = s.into_bigint().to_bytes_le();
+            for (limb, byte) in assignment.scalars[i].iter_mut().zip(bytes) {
+                *limb = M31::from(byte as u32);
+            }
+            sum += p * *s;
+        }
+        g1_to_limbs(&sum.into_affine(), &mut assignment.r);
+
+        debug_eval(&G1MsmCircuit::default(), &assignment, hint_registry);
+    }
 }
@@ -1,3 +1,4 @@
+use ark_bls12_381::G2Affine as NativeG2;
 use circuit_std_rs::big_int::*;
 use circuit_std_rs::gnark::element::*;
 use circuit_std_rs::gnark::emparam::Bls12381Fp;
@@ -9,6 +10,12 @@ use expander_compiler::frontend::{Config, GenericDefine, M31Config, RootAPI, Var
 use num_bigint::BigInt;
 use std::str::FromStr;
 
+use crate::bls12_381::ciphersuite::Ciphersuite;
+use crate::bls12_381::fr::{Bls12381Fr, ScalarF};
+use crate::bls12_381::g1::SCALAR_MUL_WINDOW;
+use crate::bls12_381::hash_to_field::{hash_to_field, hash_to_field_var};
+use crate::bls12_381::native::{fq_to_bigint, g2_scalar_mul_offset};
+
 const M_COMPRESSED_SMALLEST: u8 = 0b100 << 5;
 const M_COMPRESSED_LARGEST: u8 = 0b101 << 5;
 
@@ -33,6 +40,21 @@ impl G2AffP {
             y: GE2::from_vars(x1, y1),
         }
     }
+    pub fn one<C: Config, B: RootAPI<C>>(native: &mut B) -> Self {
+        let x0 = value_of::<C, B, Bls12381Fp>(native, Box::new("352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160".to_string()));
+        let x1 = value_of::<C, B, Bls12381Fp>(native, Box::new("3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758".to_string()));
+        let y0 = value_of::<C, B, Bls12381Fp>(native, Box::new("1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905".to_string()));
+        let y1 = value_of::<C, B, Bls12381Fp>(native, Box::new("927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582".to_string()));
+        Self::from_vars(x0.limbs, x1.limbs, y0.limbs, y1.limbs)
+    }
+    // from_native returns a point fixed at circuit-build time
+    pub fn from_native<C: Config, B: RootAPI<C>>(native: &mut B, p: &NativeG2) -> Self {
+        let x0 = value_of::<C, B, Bls12381Fp>(native, Box::new(fq_to_bigint(&p.x.c0)));
+        let x1 = value_of::<C, B, Bls12381Fp>(native, Box::new(fq_to_bigint(&p.x.c1)));
+        let y0 = value_of::<C, B, Bls12381Fp>(native, Box::new(fq_to_bigint(&p.y.c0)));
+        let y1 = value_of::<C, B, Bls12381Fp>(native, Box::new(fq_to_bigint(&p.y.c1)));
+        Self::from_vars(x0.limbs, x1.limbs, y0.limbs, y1.limbs)
+    }
 }
 
 #[derive(Default)]
@@ -144,6 +166,210 @@ impl G2 {
 
         G2AffP::new(xr, yr)
     }
+    // select returns p if selector is 1 and q otherwise
+    pub fn select<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        selector: Variable,
+        p: &G2AffP,
+        q: &G2AffP,
+    ) -> G2AffP {
+        let x = self.ext2.select(native, selector, &p.x, &q.x);
+        let y = self.ext2.select(native, selector, &p.y, &q.y);
+        G2AffP::new(x, y)
+    }
+    // scalar_mul_bits computes [s]q for s given as little-endian bits whose top bit is 1, with
+    // the same accumulator argument as G1::scalar_mul_bits
+    pub fn scalar_mul_bits<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        q: &G2AffP,
+        bits: &[Variable],
+    ) -> G2AffP {
+        let top = bits[bits.len() - 1];
+        let one = native.constant(1);
+        native.assert_is_equal(top, one);
+        let mut res = self.copy_g2_aff_p(native, q);
+        for bit in bits.iter().rev().skip(1) {
+            res = self.g2_double(native, &res);
+            let sum = self.g2_add(native, &res, q);
+            res = self.select(native, *bit, &sum, &res);
+        }
+        res
+    }
+    // scalar_mul_var computes [s]q for q in G2 and any s != 0 mod r given as little-endian bits,
+    // with the windowed table and offset accumulator of G1::scalar_mul_var. The start point of
+    // g2_scalar_mul_offset lies outside G2 and E'(Fp2) has odd order, so the same argument holds.
+    pub fn scalar_mul_var<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        q: &G2AffP,
+        bits: &[Variable],
+    ) -> G2AffP {
+        let table = self.multiples(native, q, 1 << SCALAR_MUL_WINDOW);
+        let windows = bits
+            .chunks(SCALAR_MUL_WINDOW)
+            .map(|window| window.to_vec())
+            .collect::<Vec<_>>();
+        self.windowed_sum(native, &table, &windows, SCALAR_MUL_WINDOW)
+    }
+    // scalar_mul_gls computes [s]q for q in G2 and a canonical s != 0 mod r, the GLS analogue of
+    // G1::scalar_mul_glv. psi(q) = [x0]q on G2, so psi^2(q) = [x0^2]q and
+    // [s]q = [s1]q + [s2]psi^2(q) for s = s1 + s2 * x0^2.
+    pub fn scalar_mul_gls<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        scalar_f: &mut ScalarF,
+        q: &G2AffP,
+        s: &Element<Bls12381Fr>,
+    ) -> G2AffP {
+        let (s1_bits, s2_bits) = scalar_f.decompose_glv(native, s);
+        let psi_q = self.psi(native, q);
+        let psi2_q = self.psi(native, &psi_q);
+
+        let half = SCALAR_MUL_WINDOW / 2;
+        let q_multiples = self.multiples(native, q, 1 << half);
+        let psi2_multiples = self.multiples(native, &psi2_q, 1 << half);
+        let mut table = vec![];
+        for (j, psi2_j) in psi2_multiples.iter().enumerate() {
+            for (i, q_i) in q_multiples.iter().enumerate() {
+                table.push(match (i, j) {
+                    (_, 0) => q_i.clone(),
+                    (0, _) => psi2_j.clone(),
+                    _ => self.g2_add_unequal(native, q_i, psi2_j),
+                });
+            }
+        }
+        let windows = s1_bits
+            .chunks(half)
+            .zip(s2_bits.chunks(half))
+            .map(|(s1, s2)| [s1, s2].concat())
+            .collect::<Vec<_>>();
+        self.windowed_sum(native, &table, &windows, half)
+    }
+    // multiples returns [j]q for j < n, with q standing in for [0]q
+    fn multiples<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        q: &G2AffP,
+        n: usize,
+    ) -> Vec<G2AffP> {
+        let mut table = vec![
+            self.copy_g2_aff_p(native, q),
+            self.copy_g2_aff_p(native, q),
+            self.g2_double(native, q),
+        ];
+        for j in 3..n {
+            let entry = self.g2_add_unequal(native, &table[j - 1], q);
+            table.push(entry);
+        }
+        table
+    }
+    // windowed_sum is G1::windowed_sum over G2 for a single table
+    fn windowed_sum<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        table: &[G2AffP],
+        windows: &[Vec<Variable>],
+        doublings: usize,
+    ) -> G2AffP {
+        let (start, end) = g2_scalar_mul_offset((windows.len() - 1) * doublings);
+        let mut acc = G2AffP::from_native(native, &start);
+        for (i, window) in windows.iter().enumerate().rev() {
+            if i + 1 < windows.len() {
+                for _ in 0..doublings {
+                    acc = self.g2_double(native, &acc);
+                }
+            }
+            let entry = self.lookup(native, table, window);
+            let sum = self.g2_add_unequal(native, &acc, &entry);
+            let digit = from_binary(native, window.to_vec());
+            let is_zero = native.is_zero(digit);
+            acc = self.select(native, is_zero, &acc, &sum);
+        }
+        let end = G2AffP::from_native(native, &end);
+        let end = self.neg(native, &end);
+        self.g2_add_unequal(native, &acc, &end)
+    }
+    // lookup returns table[j] for j given as little-endian bits
+    fn lookup<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        table: &[G2AffP],
+        bits: &[Variable],
+    ) -> G2AffP {
+        let mut layer = table[..1 << bits.len()].to_vec();
+        for bit in bits.iter() {
+            layer = layer
+                .chunks(2)
+                .map(|pair| self.select(native, *bit, &pair[1], &pair[0]))
+                .collect();
+        }
+        layer.pop().unwrap()
+    }
+    // g2_add_unequal is g2_add with the incomplete case ruled out: if p.x == q.x the slope
+    // constraint degenerates to 0 == 0 and the prover could pick any λ.
+    pub fn g2_add_unequal<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &G2AffP,
+        q: &G2AffP,
+    ) -> G2AffP {
+        let qxpx = self.ext2.sub(native, &q.x, &p.x);
+        let same_x = self.ext2.is_zero(native, &qxpx);
+        native.assert_is_zero(same_x);
+        self.g2_add(native, p, q)
+    }
+    // sum_selected is G1::sum_selected over G2, with the offset of G2::scalar_mul_var
+    pub fn sum_selected<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        points: &[G2AffP],
+        bits: &[Variable],
+    ) -> G2AffP {
+        assert_eq!(points.len(), bits.len());
+        let (offset, _) = g2_scalar_mul_offset(0);
+        let offset = G2AffP::from_native(native, &offset);
+        let mut acc = offset.clone();
+        for (q, bit) in points.iter().zip(bits.iter()) {
+            let sum = self.g2_add_unequal(native, &acc, q);
+            acc = self.select(native, *bit, &sum, &acc);
+        }
+        let offset = self.neg(native, &offset);
+        self.g2_add_unequal(native, &acc, &offset)
+    }
+    // sum is sum_selected with every point selected
+    pub fn sum<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        points: &[G2AffP],
+    ) -> G2AffP {
+        let one = native.constant(1);
+        self.sum_selected(native, points, &vec![one; points.len()])
+    }
+    pub fn assert_on_curve<C: Config, B: RootAPI<C>>(&mut self, native: &mut B, p: &G2AffP) {
+        //y^2 = x^3 + 4(1 + u)
+        let xxx = self.ext2.square(native, &p.x);
+        let xxx = self.ext2.mul(native, &xxx, &p.x);
+        let b_curve_coeff = value_of::<C, B, Bls12381Fp>(native, Box::new(4));
+        let b_twist_curve_coeff =
+            GE2::from_vars(b_curve_coeff.clone().limbs, b_curve_coeff.clone().limbs);
+        let rhs = self.ext2.add(native, &xxx, &b_twist_curve_coeff);
+        let yy = self.ext2.square(native, &p.y);
+        self.ext2.assert_isequal(native, &yy, &rhs);
+    }
+    // assert_in_subgroup checks that an on-curve point q lies in the r-torsion, using
+    // [r]q == 0 <==> psi(q) == [x0]q. x0 is negative, so [x0]q = -mul_windowed(q, |x0|).
+    // The additions are guarded, so points with a small-order component cannot slip through
+    // a degenerate addition.
+    pub fn assert_in_subgroup<C: Config, B: RootAPI<C>>(&mut self, native: &mut B, q: &G2AffP) {
+        let x_big = BigInt::from_str("15132376222941642752").expect("Invalid string for BigInt");
+
+        let xq_neg = self.mul_windowed_with(native, q, x_big, true);
+        let xq = self.neg(native, &xq_neg);
+        let psi_q = self.psi(native, q);
+        self.assert_is_equal(native, &psi_q, &xq);
+    }
     pub fn psi<C: Config, B: RootAPI<C>>(&mut self, native: &mut B, q: &G2AffP) -> G2AffP {
         let x = self.ext2.mul_by_element(native, &q.x, &self.u1);
         let y = self.ext2.conjugate(native, &q.y);
@@ -155,6 +381,15 @@ impl G2 {
         native: &mut B,
         q: &G2AffP,
         s: BigInt,
+    ) -> G2AffP {
+        self.mul_windowed_with(native, q, s, false)
+    }
+    fn mul_windowed_with<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        q: &G2AffP,
+        s: BigInt,
+        guarded: bool,
     ) -> G2AffP {
         let mut ops = [
             self.copy_g2_aff_p(native, q),
@@ -162,21 +397,21 @@ impl G2 {
             self.copy_g2_aff_p(native, q),
         ];
         ops[1] = self.g2_double(native, &ops[1]);
-        ops[2] = self.g2_add(native, &ops[0], &ops[1]);
+        ops[2] = self.g2_add_maybe_unequal(native, &ops[0], &ops[1], guarded);
         let b = s.to_bytes_be();
         let b = &b.1[1..];
         let mut res = self.copy_g2_aff_p(native, &ops[2]);
 
         res = self.g2_double(native, &res);
         res = self.g2_double(native, &res);
-        res = self.g2_add(native, &res, &ops[0]);
+        res = self.g2_add_maybe_unequal(native, &res, &ops[0], guarded);
 
         res = self.g2_double(native, &res);
         res = self.g2_double(native, &res);
 
         res = self.g2_double(native, &res);
         res = self.g2_double(native, &res);
-        res = self.g2_add(native, &res, &ops[1]);
+        res = self.g2_add_maybe_unequal(native, &res, &ops[1], guarded);
         for w in b {
             let mut mask = 0xc0;
             for j in 0..4 {
@@ -184,13 +419,26 @@ impl G2 {
                 res = self.g2_double(native, &res);
                 let c = (w & mask) >> (6 - 2 * j);
                 if c != 0 {
-                    res = self.g2_add(native, &res, &ops[(c - 1) as usize]);
+                    res = self.g2_add_maybe_unequal(native, &res, &ops[(c - 1) as usize], guarded);
                 }
                 mask >>= 2;
             }
         }
         res
     }
+    fn g2_add_maybe_unequal<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &G2AffP,
+        q: &G2AffP,
+        guarded: bool,
+    ) -> G2AffP {
+        if guarded {
+            self.g2_add_unequal(native, p, q)
+        } else {
+            self.g2_add(native, p, q)
+        }
+    }
     pub fn clear_cofactor<C: Config, B: RootAPI<C>>(
         &mut self,
         native: &mut B,
@@ -440,6 +688,49 @@ impl G2 {
             GE2::from_vars(u[2].clone().limbs, u[3].clone().limbs),
         )
     }
+    // hash_to_fp_with_dst is hash_to_fp under an explicit domain separation tag
+    pub fn hash_to_fp_with_dst<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        data: &[Variable],
+        dst: &[u8],
+    ) -> (GE2, GE2) {
+        let u = hash_to_field(native, &mut self.ext2.curve_f, data, dst, 2 * 2);
+        (
+            GE2::from_vars(u[0].clone().limbs, u[1].clone().limbs),
+            GE2::from_vars(u[2].clone().limbs, u[3].clone().limbs),
+        )
+    }
+    // hash_to_g2 is hash_to_curve of RFC 9380 under the tag of suite
+    pub fn hash_to_g2<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        msg: &[Variable],
+        suite: Ciphersuite,
+    ) -> G2AffP {
+        let (hm0, hm1) = match suite.dst() {
+            Some(dst) => self.hash_to_fp_with_dst(native, msg, dst),
+            None => self.hash_to_fp(native, msg),
+        };
+        self.map_to_g2(native, &hm0, &hm1)
+    }
+    // hash_to_g2_var is hash_to_g2 over the first msg_len bytes of msg. The tag of
+    // curve_f.hash_to_fp is not available here, so suite must carry its own tag.
+    pub fn hash_to_g2_var<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        msg: &[Variable],
+        msg_len: Variable,
+        suite: Ciphersuite,
+    ) -> G2AffP {
+        let dst = suite
+            .dst()
+            .expect("variable-length hashing needs a ciphersuite with an explicit tag");
+        let u = hash_to_field_var(native, &mut self.ext2.curve_f, msg, msg_len, dst, 2 * 2);
+        let hm0 = GE2::from_vars(u[0].clone().limbs, u[1].clone().limbs);
+        let hm1 = GE2::from_vars(u[2].clone().limbs, u[3].clone().limbs);
+        self.map_to_g2(native, &hm0, &hm1)
+    }
     pub fn map_to_g2<C: Config, B: RootAPI<C>>(
         &mut self,
         native: &mut B,
@@ -532,8 +823,9 @@ impl G2 {
 
         let y = self.ext2.select(native, neg_flag, &neg_y, &y);
 
-        //TBD: subgroup check, do we need to do that? Since we are pretty sure that the sig bytes are correct, its unmashalling must be on the right curve?
-        G2AffP { x: px, y }
+        let res = G2AffP { x: px, y };
+        self.assert_in_subgroup(native, &res);
+        res
     }
 }
 
@@ -561,6 +853,28 @@ impl GenericDefine<M31Config> for G2UncompressCircuit<Variable> {
     }
 }
 
+declare_circuit!(G2SubgroupCheckCircuit {
+    q: [[[Variable; 48]; 2]; 2],
+});
+
+impl GenericDefine<M31Config> for G2SubgroupCheckCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut g2 = G2::new(builder);
+        let q = G2AffP::from_vars(
+            self.q[0][0].to_vec(),
+            self.q[0][1].to_vec(),
+            self.q[1][0].to_vec(),
+            self.q[1][1].to_vec(),
+        );
+        g2.assert_on_curve(builder, &q);
+        g2.assert_in_subgroup(builder, &q);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+    }
+}
+
 declare_circuit!(MapToG2Circuit {
     in0: [[Variable; 48]; 2],
     in1: [[Variable; 48]; 2],
@@ -607,3 +921,160 @@ impl GenericDefine<M31Config> for HashToG2Circuit<Variable> {
     }
 }
 
+// IETF hash_to_curve test vectors use their own tag, RFC 9380 J.10.1
+declare_circuit!(HashToG2QuuxCircuit {
+    msg: [Variable; 3],
+    out: [[[Variable; 48]; 2]; 2],
+});
+
+impl GenericDefine<M31Config> for HashToG2QuuxCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut g2 = G2::new(builder);
+        let suite = Ciphersuite::Custom(b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_");
+        let res = g2.hash_to_g2(builder, &self.msg, suite);
+        let target_out = G2AffP {
+            x: GE2::from_vars(self.out[0][0].to_vec(), self.out[0][1].to_vec()),
+            y: GE2::from_vars(self.out[1][0].to_vec(), self.out[1][1].to_vec()),
+        };
+        g2.assert_is_equal(builder, &res, &target_out);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+declare_circuit!(G2ScalarMulCircuit {
+    q: [[[Variable; 48]; 2]; 2],
+    s: [Variable; 32],
+    r: [[[Variable; 48]; 2]; 2],
+});
+
+impl GenericDefine<M31Config> for G2ScalarMulCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut g2 = G2::new(builder);
+        let mut scalar_f = ScalarF::new(builder);
+        let q = G2AffP::from_vars(
+            self.q[0][0].to_vec(),
+            self.q[0][1].to_vec(),
+            self.q[1][0].to_vec(),
+            self.q[1][1].to_vec(),
+        );
+        let s = scalar_f.from_canonical_bytes(builder, &self.s);
+        let bits = scalar_f.to_bits(builder, &s);
+        let res = g2.scalar_mul_var(builder, &q, &bits);
+        let expected = G2AffP::from_vars(
+            self.r[0][0].to_vec(),
+            self.r[0][1].to_vec(),
+            self.r[1][0].to_vec(),
+            self.r[1][1].to_vec(),
+        );
+        g2.assert_is_equal(builder, &res, &expected);
+        let res = g2.scalar_mul_gls(builder, &mut scalar_f, &q, &s);
+        g2.assert_is_equal(builder, &res, &expected);
+        scalar_f.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::G2ScalarMulCircuit;
+    use super::G2SubgroupCheckCircuit;
+    use super::HashToG2QuuxCircuit;
+    use crate::bls12_381::native::{g2_scalar_mul_offset, g2_to_limbs};
+    use crate::bls12_381::register_bls12381_hint;
+    use ark_bls12_381::{Fr, G2Affine as NativeG2};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{BigInteger, PrimeField};
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+    use num_bigint::BigInt;
+    use num_traits::Num;
+
+    #[test]
+    fn test_hash_to_g2_with_custom_dst() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let mut assignment = HashToG2QuuxCircuit::<M31> {
+            msg: [M31::from(0); 3],
+            out: [[[M31::from(0); 48]; 2]; 2],
+        };
+        let coords = [
+            ["02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6", "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8"],
+            ["1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48", "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16"],
+        ];
+
+        for (i, byte) in b"abc".iter().enumerate() {
+            assignment.msg[i] = M31::from(*byte as u32);
+        }
+        for (j, coord) in coords.iter().enumerate() {
+            for (k, hex) in coord.iter().enumerate() {
+                let mut bytes = BigInt::from_str_radix(hex, 16).unwrap().to_bytes_le().1;
+                bytes.resize(48, 0);
+                for i in 0..48 {
+                    assignment.out[j][k][i] = M31::from(bytes[i] as u32);
+                }
+            }
+        }
+
+        debug_eval(&HashToG2QuuxCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    fn test_g2_subgroup_check() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let mut assignment = G2SubgroupCheckCircuit::<M31> {
+            q: [[[M31::from(0); 48]; 2]; 2],
+        };
+        let q = (NativeG2::generator() * Fr::from(0x5eed)).into_affine();
+        g2_to_limbs(&q, &mut assignment.q);
+
+        debug_eval(&G2SubgroupCheckCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_g2_subgroup_check_rejects_cofactor_point() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let mut assignment = G2SubgroupCheckCircuit::<M31> {
+            q: [[[M31::from(0); 48]; 2]; 2],
+        };
+        // (2, sqrt(2^3 + 4(1 + u))) is on the twist but not in G2
+        let (q, _) = g2_scalar_mul_offset(0);
+        g2_to_limbs(&q, &mut assignment.q);
+
+        debug_eval(&G2SubgroupCheckCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    fn test_g2_scalar_mul_var() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let q = (NativeG2::generator() * Fr::from(0x5eed)).into_affine();
+        let s = -Fr::from(0x1000_0000_0000_0030u64);
+        let mut assignment = G2ScalarMulCircuit::<M31> {
+            q: [[[M31::from(0); 48]; 2]; 2],
+            s: [M31::from(0); 32],
+            r: [[[M31::from(0); 48]; 2]; 2],
+        };
+        g2_to_limbs(&q, &mut assignment.q);
+        for (limb, byte) in assignment.s.iter_mut().zip(s.into_bigint().to_bytes_le()) {
+            *limb = M31::from(byte as u32);
+        }
+        g2_to_limbs(&(q * s).into_affine(), &mut assignment.r);
+
+        debug_eval(&G2ScalarMulCircuit::default(), &assignment, hint_registry);
+    }
+}
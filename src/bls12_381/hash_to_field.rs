@@ -0,0 +1,89 @@
+use circuit_std_rs::big_int::*;
+use circuit_std_rs::gnark::element::*;
+use circuit_std_rs::gnark::emparam::{Bls12381Fp, FieldParams};
+use circuit_std_rs::gnark::emulated::field_bls12381::e2::CurveF;
+use circuit_std_rs::gnark::hints::unwrap_hint;
+use expander_compiler::frontend::{Config, Error, RootAPI, Variable, M31};
+
+use crate::hash::expand_message::{expand_message_xmd, expand_message_xmd_var};
+
+// 2^384 mod p
+const TWO_384_MOD_P: &str = "3380320199399472671518931668520476396067793891014375699959770179129436917079669831430077592723774664465579537268733";
+
+// hash_to_field of RFC 9380 5.2 with expand_message_xmd(SHA-256) and L = 64. Unlike
+// curve_f.hash_to_fp it takes the domain separation tag as a parameter. The outputs are
+// canonical, so they can be used wherever the outputs of curve_f.hash_to_fp are.
+pub fn hash_to_field<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    curve_f: &mut CurveF,
+    msg: &[Variable],
+    dst: &[u8],
+    count: usize,
+) -> Vec<Element<Bls12381Fp>> {
+    let uniform_bytes = expand_message_xmd(native, msg, dst, count * 64);
+    field_elements(native, curve_f, &uniform_bytes)
+}
+
+// hash_to_field_var is hash_to_field over the first msg_len bytes of msg
+pub fn hash_to_field_var<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    curve_f: &mut CurveF,
+    msg: &[Variable],
+    msg_len: Variable,
+    dst: &[u8],
+    count: usize,
+) -> Vec<Element<Bls12381Fp>> {
+    let uniform_bytes = expand_message_xmd_var(native, msg, msg_len, dst, count * 64);
+    field_elements(native, curve_f, &uniform_bytes)
+}
+
+fn field_elements<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    curve_f: &mut CurveF,
+    uniform_bytes: &[Variable],
+) -> Vec<Element<Bls12381Fp>> {
+    let shift = value_of::<C, B, Bls12381Fp>(native, Box::new(TWO_384_MOD_P.to_string()));
+    let zero = native.constant(0);
+    let mut res = vec![];
+    for chunk in uniform_bytes.chunks(64) {
+        //the 64 big-endian bytes are hi * 2^384 + lo with a 16-byte hi and a 48-byte lo
+        let lo = new_internal_element::<Bls12381Fp>(chunk[16..].iter().rev().cloned().collect(), 0);
+        let hi_limbs = [chunk[..16].iter().rev().cloned().collect(), vec![zero; 32]].concat();
+        let hi = new_internal_element::<Bls12381Fp>(hi_limbs, 0);
+        let hi_shifted = curve_f.mul(native, &hi, &shift);
+        let e = curve_f.add(native, &lo, &hi_shifted);
+        res.push(reduce_strict(native, curve_f, &e));
+    }
+    res
+}
+
+// reduce_strict returns the canonical representative of a, pinned by equality mod p and r < p
+pub fn reduce_strict<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    curve_f: &mut CurveF,
+    a: &Element<Bls12381Fp>,
+) -> Element<Bls12381Fp> {
+    let outputs = curve_f.new_hint(native, "myhint.bls12381fpreducehint", 1, vec![a.my_clone()]);
+    let r = outputs[0].clone();
+    curve_f.assert_is_equal(native, &r, a);
+    let modulus = value_of::<C, B, Bls12381Fp>(native, Box::new(Bls12381Fp::modulus()));
+    let is_reduced = big_less_than(
+        native,
+        Bls12381Fp::bits_per_limb() as usize,
+        Bls12381Fp::nb_limbs() as usize,
+        &r.limbs,
+        &modulus.limbs,
+    );
+    let one = native.constant(1);
+    native.assert_is_equal(is_reduced, one);
+    r
+}
+
+pub fn fp_reduce_hint(inputs: &[M31], outputs: &mut [M31]) -> Result<(), Error> {
+    if let Err(err) = unwrap_hint(true, true, inputs, outputs, |inputs| {
+        Ok(vec![&inputs[0] % Bls12381Fp::modulus()])
+    }) {
+        panic!("fp_reduce_hint: {}", err);
+    }
+    Ok(())
+}
@@ -1,3 +1,19 @@
+use expander_compiler::frontend::{HintRegistry, M31};
+
+pub mod batch;
+pub mod ciphersuite;
+pub mod fr;
 pub mod g1;
 pub mod g2;
-pub mod pairing;
\ No newline at end of file
+pub mod hash_to_field;
+pub mod native;
+pub mod pairing;
+pub mod pop;
+pub mod signature;
+pub mod threshold;
+
+pub fn register_bls12381_hint(hint_registry: &mut HintRegistry<M31>) {
+    hint_registry.register("myhint.bls12381fpreducehint", hash_to_field::fp_reduce_hint);
+    hint_registry.register("myhint.bls12381frreducehint", fr::fr_reduce_hint);
+    hint_registry.register("myhint.bls12381frglvhint", fr::fr_glv_hint);
+}
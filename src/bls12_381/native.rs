@@ -0,0 +1,101 @@
+use ark_bls12_381::{g1, g2, Fq, Fq2, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::hashing::{
+    curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve,
+};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{field_hashers::DefaultFieldHasher, BigInteger, PrimeField, Zero};
+use expander_compiler::frontend::M31;
+use num_bigint::{BigInt, Sign};
+
+// Out-of-circuit counterparts of the gadgets, used to build assignments and test vectors.
+
+pub fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Affine {
+    MapToCurveBasedHasher::<G1Projective, DefaultFieldHasher<sha2::Sha256, 128>, WBMap<g1::Config>>::new(dst)
+        .unwrap()
+        .hash(msg)
+        .unwrap()
+}
+
+pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Affine {
+    MapToCurveBasedHasher::<G2Projective, DefaultFieldHasher<sha2::Sha256, 128>, WBMap<g2::Config>>::new(dst)
+        .unwrap()
+        .hash(msg)
+        .unwrap()
+}
+
+// compress_g1 is the zcash encoding read by G1::uncompressed
+pub fn compress_g1(p: &G1Affine) -> Vec<u8> {
+    let mut bytes = p.x.into_bigint().to_bytes_be();
+    let neg_y = -p.y;
+    bytes[0] |= 0b100 << 5;
+    if p.y.into_bigint() > neg_y.into_bigint() {
+        bytes[0] |= 0b001 << 5;
+    }
+    bytes
+}
+
+// compress_g2 is the zcash encoding read by G2::uncompressed: x.c1 || x.c0 with the flags in the
+// first byte, y being the largest root when y.c1 is, or y.c0 is and y.c1 = 0
+pub fn compress_g2(p: &G2Affine) -> Vec<u8> {
+    let mut bytes = p.x.c1.into_bigint().to_bytes_be();
+    bytes.extend(p.x.c0.into_bigint().to_bytes_be());
+    let neg_y = -p.y;
+    bytes[0] |= 0b100 << 5;
+    let is_large = if p.y.c1.is_zero() {
+        p.y.c0.into_bigint() > neg_y.c0.into_bigint()
+    } else {
+        p.y.c1.into_bigint() > neg_y.c1.into_bigint()
+    };
+    if is_large {
+        bytes[0] |= 0b001 << 5;
+    }
+    bytes
+}
+
+// fq_to_limbs writes a base field element as 48 little-endian byte limbs
+pub fn fq_to_limbs(v: &Fq, out: &mut [M31; 48]) {
+    let bytes = v.into_bigint().to_bytes_le();
+    for (limb, byte) in out.iter_mut().zip(bytes) {
+        *limb = M31::from(byte as u32);
+    }
+}
+
+pub fn fq_to_bigint(v: &Fq) -> BigInt {
+    BigInt::from_bytes_le(Sign::Plus, &v.into_bigint().to_bytes_le())
+}
+
+pub fn g1_to_limbs(p: &G1Affine, out: &mut [[M31; 48]; 2]) {
+    fq_to_limbs(&p.x, &mut out[0]);
+    fq_to_limbs(&p.y, &mut out[1]);
+}
+
+pub fn g2_to_limbs(p: &G2Affine, out: &mut [[[M31; 48]; 2]; 2]) {
+    fq_to_limbs(&p.x.c0, &mut out[0][0]);
+    fq_to_limbs(&p.x.c1, &mut out[0][1]);
+    fq_to_limbs(&p.y.c0, &mut out[1][0]);
+    fq_to_limbs(&p.y.c1, &mut out[1][1]);
+}
+
+// g1_scalar_mul_offset returns the point A = (5, y), which is on the curve but outside G1, and
+// [2^doublings]A. They are the start and the correction of the accumulator of G1::scalar_mul_var.
+pub fn g1_scalar_mul_offset(doublings: usize) -> (G1Affine, G1Affine) {
+    let a = G1Affine::get_point_from_x_unchecked(Fq::from(5u64), false).unwrap();
+    assert!(!a.is_in_correct_subgroup_assuming_on_curve());
+    let mut end: G1Projective = a.into();
+    for _ in 0..doublings {
+        end.double_in_place();
+    }
+    (a, end.into_affine())
+}
+
+// g2_scalar_mul_offset is g1_scalar_mul_offset for G2, with A = (2, y) on the twist
+pub fn g2_scalar_mul_offset(doublings: usize) -> (G2Affine, G2Affine) {
+    let x = Fq2::new(Fq::from(2u64), Fq::zero());
+    let a = G2Affine::get_point_from_x_unchecked(x, false).unwrap();
+    assert!(!a.is_in_correct_subgroup_assuming_on_curve());
+    let mut end: G2Projective = a.into();
+    for _ in 0..doublings {
+        end.double_in_place();
+    }
+    (a, end.into_affine())
+}
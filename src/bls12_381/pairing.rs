@@ -32,15 +32,24 @@ impl Pairing {
         q: &mut [G2Affine],
     ) -> Result<(), Error> {
         let f = self.miller_loop(native, p, q).unwrap();
-        let buf = self.ext12.conjugate(native, &f);
+        self.assert_final_exponentiation_is_one(native, &f);
 
-        let buf = self.ext12.div(native, &buf, &f);
+        Ok(())
+    }
+    // assert_final_exponentiation_is_one checks f^((p^12 - 1) / r) = 1 for a Miller loop output
+    // f, so a product of Miller loops pays for a single final exponentiation
+    pub fn assert_final_exponentiation_is_one<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        f: &GE12,
+    ) {
+        let buf = self.ext12.conjugate(native, f);
+
+        let buf = self.ext12.div(native, &buf, f);
         let f = self.ext12.frobenius_square(native, &buf);
         let f = self.ext12.mul(native, &f, &buf);
 
         self.ext12.assert_final_exponentiation_is_one(native, &f);
-
-        Ok(())
     }
     pub fn miller_loop<C: Config, B: RootAPI<C>>(
         &mut self,
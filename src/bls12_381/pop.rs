@@ -0,0 +1,115 @@
+use crate::bls12_381::{ciphersuite::Ciphersuite, g1::*, g2::*, pairing::Pairing};
+use expander_compiler::{
+    declare_circuit,
+    frontend::{GenericDefine, M31Config, RootAPI, Variable},
+};
+
+declare_circuit!(PopVerifyCircuit {
+    pub_key: [Variable; 48],
+    pop_sig: [[[Variable; 48]; 2]; 2],
+});
+
+// PopVerify: e(pk, H_pop(pk_bytes)) = e(g1, pop_sig), where pk_bytes is the compressed key.
+// Uncompressing the key in-circuit ties the hashed bytes to the point used in the pairing.
+impl GenericDefine<M31Config> for PopVerifyCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut pairing = Pairing::new(builder);
+        let mut g1 = G1::new(builder);
+        let pk = g1.uncompressed(builder, &self.pub_key);
+        let g1_gen = G1Affine::one(builder);
+        let g1_neg = g1.neg(builder, &g1_gen);
+
+        let mut g2 = G2::new(builder);
+        let pop_sig = G2AffP::from_vars(
+            self.pop_sig[0][0].to_vec(),
+            self.pop_sig[0][1].to_vec(),
+            self.pop_sig[1][0].to_vec(),
+            self.pop_sig[1][1].to_vec(),
+        );
+        g2.assert_on_curve(builder, &pop_sig);
+        g2.assert_in_subgroup(builder, &pop_sig);
+
+        let msg_g2 = g2.hash_to_g2(builder, &self.pub_key, Ciphersuite::G2PopProof);
+
+        pairing
+            .pairing_check(
+                builder,
+                &[g1_neg, pk],
+                &mut [
+                    G2Affine {
+                        p: pop_sig,
+                        lines: LineEvaluations::default(),
+                    },
+                    G2Affine {
+                        p: msg_g2,
+                        lines: LineEvaluations::default(),
+                    },
+                ],
+            )
+            .unwrap();
+
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.check_mul(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PopVerifyCircuit;
+    use crate::bls12_381::ciphersuite::Ciphersuite;
+    use crate::bls12_381::native::*;
+    use crate::bls12_381::register_bls12381_hint;
+    use ark_bls12_381::{Fr, G1Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    fn pop_assignment(sk: u64) -> PopVerifyCircuit<M31> {
+        let sk = Fr::from(sk);
+        let pk = (G1Affine::generator() * sk).into_affine();
+        let pk_bytes = compress_g1(&pk);
+        let h = hash_to_g2(&pk_bytes, Ciphersuite::G2PopProof.dst().unwrap());
+        let pop_sig = (h * sk).into_affine();
+
+        let mut assignment = PopVerifyCircuit::<M31> {
+            pub_key: [M31::from(0); 48],
+            pop_sig: [[[M31::from(0); 48]; 2]; 2],
+        };
+        for i in 0..48 {
+            assignment.pub_key[i] = M31::from(pk_bytes[i] as u32);
+        }
+        g2_to_limbs(&pop_sig, &mut assignment.pop_sig);
+        assignment
+    }
+
+    #[test]
+    fn test_pop_verify() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        debug_eval(&PopVerifyCircuit::default(), &pop_assignment(0x5eed), hint_registry);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pop_verify_rejects_other_key() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let mut assignment = pop_assignment(0x5eed);
+        assignment.pub_key = pop_assignment(0x5eee).pub_key;
+        debug_eval(&PopVerifyCircuit::default(), &assignment, hint_registry);
+    }
+}
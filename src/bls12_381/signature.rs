@@ -0,0 +1,263 @@
+use crate::bls12_381::{ciphersuite::Ciphersuite, g1::*, g2::*, pairing::Pairing};
+use expander_compiler::{
+    declare_circuit,
+    frontend::{Config, GenericDefine, M31Config, RootAPI, Variable},
+};
+
+// capacity of the msg input of the variable-length circuits, msg_len selects the signed prefix
+pub const BLS_MAX_MSG_LEN: usize = 256;
+
+// the variable-length circuits hash under the Ethereum ciphersuite
+pub const VAR_MSG_CIPHERSUITE: Ciphersuite = Ciphersuite::G2Pop;
+
+declare_circuit!(BLSVarMsgSignatureCircuit {
+    pub_key: [[Variable; 48]; 2],
+    sig: [[[Variable; 48]; 2]; 2],
+    msg: [Variable; BLS_MAX_MSG_LEN],
+    msg_len: Variable,
+});
+
+// Verify: e(-g1, sig) * e(pk, H(msg[..msg_len])) = 1
+impl GenericDefine<M31Config> for BLSVarMsgSignatureCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut pairing = Pairing::new(builder);
+        let mut g1 = G1::new(builder);
+        let pk = G1Affine::from_vars(self.pub_key[0].to_vec(), self.pub_key[1].to_vec());
+        g1.assert_on_curve(builder, &pk);
+        g1.assert_in_subgroup(builder, &pk);
+        let g1_gen = G1Affine::one(builder);
+        let g1_neg = g1.neg(builder, &g1_gen);
+
+        let mut g2 = G2::new(builder);
+        let sig = G2AffP::from_vars(
+            self.sig[0][0].to_vec(),
+            self.sig[0][1].to_vec(),
+            self.sig[1][0].to_vec(),
+            self.sig[1][1].to_vec(),
+        );
+        g2.assert_on_curve(builder, &sig);
+        g2.assert_in_subgroup(builder, &sig);
+        let msg_g2 = g2.hash_to_g2_var(builder, &self.msg, self.msg_len, VAR_MSG_CIPHERSUITE);
+
+        pairing
+            .pairing_check(
+                builder,
+                &[g1_neg, pk],
+                &mut [
+                    G2Affine {
+                        p: sig,
+                        lines: LineEvaluations::default(),
+                    },
+                    G2Affine {
+                        p: msg_g2,
+                        lines: LineEvaluations::default(),
+                    },
+                ],
+            )
+            .unwrap();
+
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.check_mul(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+pub const AGGREGATE_VERIFY_SIZE: usize = 8;
+pub const AGGREGATE_VERIFY_MSG_LEN: usize = 32;
+
+// AggregateVerify of the basic scheme, which only rules out rogue keys by requiring distinct
+// messages, so it hashes under the NUL tag
+pub const AGGREGATE_VERIFY_CIPHERSUITE: Ciphersuite = Ciphersuite::G2Nul;
+
+declare_circuit!(BLSAggregateVerifyCircuit {
+    pub_keys: [[[Variable; 48]; 2]; AGGREGATE_VERIFY_SIZE],
+    msgs: [[Variable; AGGREGATE_VERIFY_MSG_LEN]; AGGREGATE_VERIFY_SIZE],
+    agg_sig: [[[Variable; 48]; 2]; 2],
+});
+
+// AggregateVerify (draft-irtf-cfrg-bls-signature 3.1.1):
+//   e(-g1, agg_sig) * prod e(pk_i, H(m_i)) = 1, with m_i pairwise distinct
+impl GenericDefine<M31Config> for BLSAggregateVerifyCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        for i in 0..AGGREGATE_VERIFY_SIZE {
+            for j in i + 1..AGGREGATE_VERIFY_SIZE {
+                assert_bytes_differ(builder, &self.msgs[i], &self.msgs[j]);
+            }
+        }
+
+        let mut pairing = Pairing::new(builder);
+        let mut g1 = G1::new(builder);
+        let g1_gen = G1Affine::one(builder);
+        let mut ps = vec![g1.neg(builder, &g1_gen)];
+        for pk in self.pub_keys.iter() {
+            let pk = G1Affine::from_vars(pk[0].to_vec(), pk[1].to_vec());
+            g1.assert_on_curve(builder, &pk);
+            g1.assert_in_subgroup(builder, &pk);
+            ps.push(pk);
+        }
+
+        let mut g2 = G2::new(builder);
+        let agg_sig = G2AffP::from_vars(
+            self.agg_sig[0][0].to_vec(),
+            self.agg_sig[0][1].to_vec(),
+            self.agg_sig[1][0].to_vec(),
+            self.agg_sig[1][1].to_vec(),
+        );
+        g2.assert_on_curve(builder, &agg_sig);
+        g2.assert_in_subgroup(builder, &agg_sig);
+        let mut qs = vec![G2Affine {
+            p: agg_sig,
+            lines: LineEvaluations::default(),
+        }];
+        for msg in self.msgs.iter() {
+            qs.push(G2Affine {
+                p: g2.hash_to_g2(builder, msg, AGGREGATE_VERIFY_CIPHERSUITE),
+                lines: LineEvaluations::default(),
+            });
+        }
+
+        pairing.pairing_check(builder, &ps, &mut qs).unwrap();
+
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.check_mul(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+// assert_bytes_differ fails when a and b are equal byte strings
+fn assert_bytes_differ<C: Config, B: RootAPI<C>>(native: &mut B, a: &[Variable], b: &[Variable]) {
+    let mut all_equal = native.constant(1);
+    for (x, y) in a.iter().zip(b) {
+        let diff = native.sub(*x, *y);
+        let equal = native.is_zero(diff);
+        all_equal = native.mul(all_equal, equal);
+    }
+    native.assert_is_zero(all_equal);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls12_381::native::*;
+    use crate::bls12_381::register_bls12381_hint;
+    use ark_bls12_381::{Fr, G1Affine, G2Projective};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::Zero;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    // a 100-byte message, longer than one SHA-256 block once the tag is appended
+    fn var_msg_assignment() -> BLSVarMsgSignatureCircuit<M31> {
+        let sk = Fr::from(0x5eed);
+        let msg = (0..100u8).collect::<Vec<_>>();
+        let pk = (G1Affine::generator() * sk).into_affine();
+        let sig = (hash_to_g2(&msg, VAR_MSG_CIPHERSUITE.dst().unwrap()) * sk).into_affine();
+
+        let mut assignment = BLSVarMsgSignatureCircuit::<M31> {
+            pub_key: [[M31::from(0); 48]; 2],
+            sig: [[[M31::from(0); 48]; 2]; 2],
+            msg: [M31::from(0); BLS_MAX_MSG_LEN],
+            msg_len: M31::from(msg.len() as u32),
+        };
+        g1_to_limbs(&pk, &mut assignment.pub_key);
+        g2_to_limbs(&sig, &mut assignment.sig);
+        for (i, byte) in msg.iter().enumerate() {
+            assignment.msg[i] = M31::from(*byte as u32);
+        }
+        assignment
+    }
+
+    #[test]
+    fn test_var_msg_signature() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let mut assignment = var_msg_assignment();
+        //bytes past msg_len are not signed
+        assignment.msg[200] = M31::from(1);
+        debug_eval(&BLSVarMsgSignatureCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_var_msg_signature_rejects_truncated_message() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let mut assignment = var_msg_assignment();
+        assignment.msg_len = M31::from(99);
+        debug_eval(&BLSVarMsgSignatureCircuit::default(), &assignment, hint_registry);
+    }
+
+    fn aggregate_assignment(
+        msgs: [[u8; AGGREGATE_VERIFY_MSG_LEN]; AGGREGATE_VERIFY_SIZE],
+    ) -> BLSAggregateVerifyCircuit<M31> {
+        let mut assignment = BLSAggregateVerifyCircuit::<M31> {
+            pub_keys: [[[M31::from(0); 48]; 2]; AGGREGATE_VERIFY_SIZE],
+            msgs: [[M31::from(0); AGGREGATE_VERIFY_MSG_LEN]; AGGREGATE_VERIFY_SIZE],
+            agg_sig: [[[M31::from(0); 48]; 2]; 2],
+        };
+        let dst = AGGREGATE_VERIFY_CIPHERSUITE.dst().unwrap();
+        let mut agg_sig = G2Projective::zero();
+        for (i, msg) in msgs.iter().enumerate() {
+            let sk = Fr::from(0x5eed + i as u64);
+            g1_to_limbs(&(G1Affine::generator() * sk).into_affine(), &mut assignment.pub_keys[i]);
+            agg_sig += hash_to_g2(msg, dst) * sk;
+            for (j, byte) in msg.iter().enumerate() {
+                assignment.msgs[i][j] = M31::from(*byte as u32);
+            }
+        }
+        g2_to_limbs(&agg_sig.into_affine(), &mut assignment.agg_sig);
+        assignment
+    }
+
+    fn distinct_msgs() -> [[u8; AGGREGATE_VERIFY_MSG_LEN]; AGGREGATE_VERIFY_SIZE] {
+        let mut msgs = [[0x56; AGGREGATE_VERIFY_MSG_LEN]; AGGREGATE_VERIFY_SIZE];
+        for (i, msg) in msgs.iter_mut().enumerate() {
+            msg[0] = i as u8;
+        }
+        msgs
+    }
+
+    #[test]
+    fn test_aggregate_verify() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let assignment = aggregate_assignment(distinct_msgs());
+        debug_eval(&BLSAggregateVerifyCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_aggregate_verify_rejects_repeated_message() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        //the aggregate is still a valid signature, only the distinctness check fails
+        let mut msgs = distinct_msgs();
+        msgs[3] = msgs[5];
+        let assignment = aggregate_assignment(msgs);
+        debug_eval(&BLSAggregateVerifyCircuit::default(), &assignment, hint_registry);
+    }
+}
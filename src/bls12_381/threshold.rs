@@ -0,0 +1,172 @@
+use crate::bls12_381::{ciphersuite::Ciphersuite, fr::ScalarF, g1::*, g2::*, pairing::Pairing};
+use expander_compiler::{
+    declare_circuit,
+    frontend::{GenericDefine, M31Config, RootAPI, Variable},
+};
+
+pub const THRESHOLD: usize = 3;
+pub const THRESHOLD_MSG_LEN: usize = 32;
+
+// drand-style threshold networks sign under the basic scheme
+pub const THRESHOLD_CIPHERSUITE: Ciphersuite = Ciphersuite::G2Nul;
+
+declare_circuit!(ThresholdSignatureCircuit {
+    group_pub_key: [[Variable; 48]; 2],
+    partial_sigs: [[[[Variable; 48]; 2]; 2]; THRESHOLD],
+    // evaluation points x_i of the signers, distinct and in 1..2^16
+    signer_indices: [Variable; THRESHOLD],
+    msg: [Variable; THRESHOLD_MSG_LEN],
+});
+
+// Recovers sig = sum λ_i σ_i with λ_i = prod_{j != i} x_j / (x_j - x_i), the Lagrange
+// coefficients at 0 over Fr, and verifies e(-g1, sig) * e(group_pk, H(msg)) = 1.
+impl GenericDefine<M31Config> for ThresholdSignatureCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut scalar_f = ScalarF::new(builder);
+
+        //the indices are below 2^16, so they differ in Fr exactly when they differ natively
+        let mut xs = vec![];
+        for (i, x) in self.signer_indices.iter().enumerate() {
+            builder.assert_is_non_zero(*x);
+            for y in self.signer_indices.iter().skip(i + 1) {
+                let diff = builder.sub(*x, *y);
+                builder.assert_is_non_zero(diff);
+            }
+            xs.push(scalar_f.from_small(builder, *x));
+        }
+
+        let mut lambda_bits = vec![];
+        for (i, x_i) in xs.iter().enumerate() {
+            let mut num = scalar_f.field.one_const.clone();
+            let mut den = scalar_f.field.one_const.clone();
+            for (j, x_j) in xs.iter().enumerate() {
+                if j != i {
+                    num = scalar_f.mul(builder, &num, x_j);
+                    let diff = scalar_f.sub(builder, x_j, x_i);
+                    den = scalar_f.mul(builder, &den, &diff);
+                }
+            }
+            let lambda = scalar_f.div(builder, &num, &den);
+            let lambda = scalar_f.reduce(builder, &lambda);
+            lambda_bits.push(scalar_f.to_bits(builder, &lambda));
+        }
+
+        //every σ_i must be in G2, where λ_i only matters mod r. The prover picks the σ_i, so the
+        //scaled shares are summed with guarded additions.
+        let mut g2 = G2::new(builder);
+        let mut scaled = vec![];
+        for (partial, bits) in self.partial_sigs.iter().zip(lambda_bits.iter()) {
+            let partial = G2AffP::from_vars(
+                partial[0][0].to_vec(),
+                partial[0][1].to_vec(),
+                partial[1][0].to_vec(),
+                partial[1][1].to_vec(),
+            );
+            g2.assert_on_curve(builder, &partial);
+            g2.assert_in_subgroup(builder, &partial);
+            scaled.push(g2.scalar_mul_var(builder, &partial, bits));
+        }
+        let sig = g2.sum(builder, &scaled);
+
+        let mut g1 = G1::new(builder);
+        let group_pk =
+            G1Affine::from_vars(self.group_pub_key[0].to_vec(), self.group_pub_key[1].to_vec());
+        g1.assert_on_curve(builder, &group_pk);
+        g1.assert_in_subgroup(builder, &group_pk);
+        let g1_gen = G1Affine::one(builder);
+        let g1_neg = g1.neg(builder, &g1_gen);
+        let msg_g2 = g2.hash_to_g2(builder, &self.msg, THRESHOLD_CIPHERSUITE);
+
+        let mut pairing = Pairing::new(builder);
+        pairing
+            .pairing_check(
+                builder,
+                &[g1_neg, group_pk],
+                &mut [
+                    G2Affine {
+                        p: sig,
+                        lines: LineEvaluations::default(),
+                    },
+                    G2Affine {
+                        p: msg_g2,
+                        lines: LineEvaluations::default(),
+                    },
+                ],
+            )
+            .unwrap();
+
+        scalar_f.final_check(builder);
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.check_mul(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls12_381::native::*;
+    use crate::bls12_381::register_bls12381_hint;
+    use ark_bls12_381::{Fr, G1Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    // shares of f(x) = 0x5eed + 17x + 29x^2, whose degree makes any THRESHOLD shares sufficient
+    fn threshold_assignment(indices: [u64; THRESHOLD]) -> ThresholdSignatureCircuit<M31> {
+        let coeffs = [Fr::from(0x5eed), Fr::from(17), Fr::from(29)];
+        let f = |x: u64| {
+            let x = Fr::from(x);
+            coeffs[0] + coeffs[1] * x + coeffs[2] * x * x
+        };
+        let msg = [0x56u8; THRESHOLD_MSG_LEN];
+        let h = hash_to_g2(&msg, THRESHOLD_CIPHERSUITE.dst().unwrap());
+
+        let mut assignment = ThresholdSignatureCircuit::<M31> {
+            group_pub_key: [[M31::from(0); 48]; 2],
+            partial_sigs: [[[[M31::from(0); 48]; 2]; 2]; THRESHOLD],
+            signer_indices: [M31::from(0); THRESHOLD],
+            msg: [M31::from(0x56); THRESHOLD_MSG_LEN],
+        };
+        g1_to_limbs(
+            &(G1Affine::generator() * coeffs[0]).into_affine(),
+            &mut assignment.group_pub_key,
+        );
+        for (i, x) in indices.iter().enumerate() {
+            g2_to_limbs(&(h * f(*x)).into_affine(), &mut assignment.partial_sigs[i]);
+            assignment.signer_indices[i] = M31::from(*x as u32);
+        }
+        assignment
+    }
+
+    #[test]
+    fn test_threshold_signature() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let assignment = threshold_assignment([2, 5, 7]);
+        debug_eval(&ThresholdSignatureCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_threshold_signature_rejects_wrong_index() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        let mut assignment = threshold_assignment([2, 5, 7]);
+        assignment.signer_indices[1] = M31::from(6);
+        debug_eval(&ThresholdSignatureCircuit::default(), &assignment, hint_registry);
+    }
+}
@@ -0,0 +1,190 @@
+use circuit_std_rs::gnark::element::*;
+use expander_compiler::{
+    declare_circuit,
+    frontend::{GenericDefine, M31Config, RootAPI, Variable, M31},
+};
+
+use crate::ed25519::curve::*;
+use crate::ed25519::eddsa::*;
+use crate::ed25519::native::sign;
+use crate::ed25519::scalar::*;
+use crate::hash::sha512::sha512;
+
+pub const ED25519_BATCH_SIZE: usize = 512;
+
+// z_i are 128-bit, which bounds the chance that an invalid batch passes by 2^-128
+const RANDOMIZER_BYTES: usize = 16;
+
+declare_circuit!(Ed25519BatchVerifyCircuit {
+    pub_keys: [[Variable; 32]; ED25519_BATCH_SIZE],
+    sigs: [[Variable; 64]; ED25519_BATCH_SIZE],
+    msgs: [[Variable; ED25519_MAX_MSG_LEN]; ED25519_BATCH_SIZE],
+    msg_lens: [Variable; ED25519_BATCH_SIZE],
+});
+
+// Checks all signatures with one equation,
+//   [8]([sum z_i S_i]B - sum [z_i]R_i - sum [z_i k_i]A_i) = O,
+// where the z_i are derived from a SHA-512 transcript of every (S_i, k_i). k_i = H(R_i || A_i ||
+// M_i) already binds the rest of signature i, so the z_i are fixed only after all signatures
+// are, and a prover cannot pick invalid signatures whose errors cancel.
+//
+// The fixed-base multiplication is paid once, and the variable-base terms go through one
+// Ed25519::msm, whose doublings are shared by the batch. Per signature in a batch of n, with
+// z_i of 128 bits and z_i k_i of 253 bits:
+//
+//                        doublings    additions         SHA-512 blocks
+//   Ed25519VerifyCircuit 255          508               challenge
+//   batch                255 / n      124 + 254 / n     challenge + 3/4
+//
+// plus two multiplications mod L for z_i S_i and z_i k_i. The 3/4 block is the 64-byte
+// transcript entry and a quarter of the block that expands the seed into four z_i.
+impl GenericDefine<M31Config> for Ed25519BatchVerifyCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut ed = Ed25519::new(builder);
+        let mut scalar_f = Ed25519ScalarF::new(builder);
+
+        let mut pub_keys = vec![];
+        let mut rs = vec![];
+        let mut ss = vec![];
+        let mut ks = vec![];
+        let mut transcript = vec![];
+        for i in 0..ED25519_BATCH_SIZE {
+            pub_keys.push(ed.decompress(builder, &self.pub_keys[i]));
+            rs.push(ed.decompress(builder, &self.sigs[i][..32]));
+
+            //S < L, otherwise (R, S + L) would be a second valid signature
+            let s = new_internal_element::<Ed25519Scalar>(self.sigs[i][32..].to_vec(), 0);
+            scalar_f.assert_is_canonical(builder, &s.limbs);
+
+            let k = challenge(
+                builder,
+                &mut scalar_f,
+                &self.sigs[i][..32],
+                &self.pub_keys[i],
+                &self.msgs[i],
+                self.msg_lens[i],
+            );
+            transcript.extend_from_slice(&self.sigs[i][32..]);
+            transcript.extend_from_slice(&k.limbs);
+            ss.push(s);
+            ks.push(k);
+        }
+
+        //z_i are the 16-byte chunks of SHA-512(seed || j) for j = 0, 1, ..
+        let seed = sha512(builder, &transcript);
+        let zero = builder.constant(0);
+        let mut zs = vec![];
+        let mut counter = 0u32;
+        while zs.len() < ED25519_BATCH_SIZE {
+            let counter_bytes = [
+                builder.constant(counter & 0xff),
+                builder.constant(counter >> 8),
+            ];
+            let out = sha512(builder, &[seed.clone(), counter_bytes.to_vec()].concat());
+            for chunk in out.chunks(RANDOMIZER_BYTES) {
+                if zs.len() < ED25519_BATCH_SIZE {
+                    let limbs = [chunk.to_vec(), vec![zero; 32 - RANDOMIZER_BYTES]].concat();
+                    zs.push(new_internal_element::<Ed25519Scalar>(limbs, 0));
+                }
+            }
+            counter += 1;
+        }
+
+        //[sum z_i S_i]B
+        let mut zs_sum = scalar_f.mul(builder, &zs[0], &ss[0]);
+        for i in 1..ED25519_BATCH_SIZE {
+            let zs_i = scalar_f.mul(builder, &zs[i], &ss[i]);
+            zs_sum = scalar_f.add(builder, &zs_sum, &zs_i);
+        }
+        let zs_sum = scalar_f.reduce(builder, &zs_sum);
+        let zs_sum_bits = scalar_f.to_bits(builder, &zs_sum);
+        let lhs = ed.base_mul_bits(builder, &zs_sum_bits);
+
+        //sum [z_i]R_i + [z_i k_i mod L]A_i
+        let mut points = vec![];
+        let mut scalars = vec![];
+        for i in 0..ED25519_BATCH_SIZE {
+            let z_bits = scalar_f.to_bits(builder, &zs[i]);
+            let zk = scalar_f.mul(builder, &zs[i], &ks[i]);
+            let zk = scalar_f.reduce(builder, &zk);
+            points.push(rs[i].clone());
+            scalars.push(z_bits[..RANDOMIZER_BYTES * 8].to_vec());
+            points.push(pub_keys[i].clone());
+            scalars.push(scalar_f.to_bits(builder, &zk));
+        }
+        let rhs = ed.msm(builder, &points, &scalars);
+
+        let rhs_neg = ed.neg(builder, &rhs);
+        let mut diff = ed.add(builder, &lhs, &rhs_neg);
+        for _ in 0..3 {
+            diff = ed.double(builder, &diff);
+        }
+        let identity = ed.identity();
+        ed.assert_is_equal(builder, &diff, &identity);
+
+        ed.curve_f.final_check(builder);
+        scalar_f.final_check(builder);
+    }
+}
+
+// batch_reference_assignment fills slot i with a signature of its own message, i as two
+// little-endian bytes padded with 0x56 to 32 + i % 64 bytes, under the key derived from the
+// seed [i % 8; 32]
+pub fn batch_reference_assignment() -> Ed25519BatchVerifyCircuit<M31> {
+    let mut assignment = Ed25519BatchVerifyCircuit::<M31> {
+        pub_keys: [[M31::from(0); 32]; ED25519_BATCH_SIZE],
+        sigs: [[M31::from(0); 64]; ED25519_BATCH_SIZE],
+        msgs: [[M31::from(0); ED25519_MAX_MSG_LEN]; ED25519_BATCH_SIZE],
+        msg_lens: [M31::from(0); ED25519_BATCH_SIZE],
+    };
+    for i in 0..ED25519_BATCH_SIZE {
+        let mut msg = vec![0x56u8; 32 + i % 64];
+        msg[0] = i as u8;
+        msg[1] = (i >> 8) as u8;
+        let (pub_key, sig) = sign(&[(i % 8) as u8; 32], &msg);
+        for (limb, byte) in assignment.pub_keys[i].iter_mut().zip(pub_key) {
+            *limb = M31::from(byte as u32);
+        }
+        for (limb, byte) in assignment.sigs[i].iter_mut().zip(sig) {
+            *limb = M31::from(byte as u32);
+        }
+        for (limb, byte) in assignment.msgs[i].iter_mut().zip(msg.iter()) {
+            *limb = M31::from(*byte as u32);
+        }
+        assignment.msg_lens[i] = M31::from(msg.len() as u32);
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{batch_reference_assignment, Ed25519BatchVerifyCircuit};
+    use crate::ed25519::register_ed25519_hint;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    #[test]
+    fn test_ed25519_batch_verify() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_ed25519_hint(&mut hint_registry);
+        debug_eval(
+            &Ed25519BatchVerifyCircuit::default(),
+            &batch_reference_assignment(),
+            hint_registry,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ed25519_batch_verify_rejects_one_bad_signature() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_ed25519_hint(&mut hint_registry);
+        let mut assignment = batch_reference_assignment();
+        assignment.msgs[7][0] = M31::from(0x57);
+        debug_eval(&Ed25519BatchVerifyCircuit::default(), &assignment, hint_registry);
+    }
+}
@@ -0,0 +1,383 @@
+use std::str::FromStr;
+
+use circuit_std_rs::big_int::*;
+use circuit_std_rs::gnark::element::*;
+use circuit_std_rs::gnark::emparam::FieldParams;
+use expander_compiler::{
+    declare_circuit,
+    frontend::{Config, GenericDefine, M31Config, RootAPI, Variable},
+};
+use num_bigint::BigInt;
+
+use crate::ed25519::field::*;
+use crate::ed25519::native;
+
+// Edwards coefficient d = -121665/121666 of edwards25519
+pub const D: &str = "37095705934669439343138083508754565189542113879843219016388785533085940283555";
+pub const BASE_X: &str =
+    "15112221349535400772501151409588531511454012693041857206046113283949847762202";
+pub const BASE_Y: &str =
+    "46316835694926478169428394003475163141307993866256225615783033603165251855960";
+
+// window width of Ed25519::msm
+const MSM_WINDOW: usize = 4;
+
+#[derive(Default, Clone)]
+pub struct Ed25519Affine {
+    pub x: Element<Curve25519Fp>,
+    pub y: Element<Curve25519Fp>,
+}
+impl Ed25519Affine {
+    pub fn new(x: Element<Curve25519Fp>, y: Element<Curve25519Fp>) -> Self {
+        Self { x, y }
+    }
+    pub fn from_vars(x: Vec<Variable>, y: Vec<Variable>) -> Self {
+        Self {
+            x: Element::new(x, 0, false, false, false, Variable::default()),
+            y: Element::new(y, 0, false, false, false, Variable::default()),
+        }
+    }
+}
+
+pub struct Ed25519 {
+    pub curve_f: Curve25519F,
+    pub d: Element<Curve25519Fp>,
+}
+
+impl Ed25519 {
+    pub fn new<C: Config, B: RootAPI<C>>(native: &mut B) -> Self {
+        let curve_f = Curve25519F::new(native);
+        let d = value_of::<C, B, Curve25519Fp>(native, Box::new(D.to_string()));
+        Self { curve_f, d }
+    }
+
+    pub fn identity(&self) -> Ed25519Affine {
+        Ed25519Affine::new(self.curve_f.zero_const.clone(), self.curve_f.one_const.clone())
+    }
+
+    pub fn neg<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &Ed25519Affine,
+    ) -> Ed25519Affine {
+        let xr = self.curve_f.neg(native, &p.x);
+        Ed25519Affine::new(xr, p.y.my_clone())
+    }
+
+    // add uses the unified twisted Edwards law with a = -1. It is complete on edwards25519 since d
+    // is not a square, so doubling, the identity and points of small order need no special case.
+    pub fn add<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &Ed25519Affine,
+        q: &Ed25519Affine,
+    ) -> Ed25519Affine {
+        let x1y2 = self.curve_f.mul(native, &p.x, &q.y);
+        let y1x2 = self.curve_f.mul(native, &p.y, &q.x);
+        let y1y2 = self.curve_f.mul(native, &p.y, &q.y);
+        let x1x2 = self.curve_f.mul(native, &p.x, &q.x);
+        let t = self.curve_f.mul(native, &x1x2, &y1y2);
+        let dt = self.curve_f.mul(native, &self.d, &t);
+        let one = self.curve_f.one_const.clone();
+
+        //x3 = (x1y2 + y1x2) / (1 + d*x1x2y1y2)
+        let x_num = self.curve_f.add(native, &x1y2, &y1x2);
+        let x_den = self.curve_f.add(native, &one, &dt);
+        let x3 = self.curve_f.div(native, &x_num, &x_den);
+
+        //y3 = (y1y2 + x1x2) / (1 - d*x1x2y1y2)
+        let y_num = self.curve_f.add(native, &y1y2, &x1x2);
+        let y_den = self.curve_f.sub(native, &one, &dt);
+        let y3 = self.curve_f.div(native, &y_num, &y_den);
+
+        Ed25519Affine::new(x3, y3)
+    }
+
+    // double substitutes the curve equation into the unified law to drop the d term
+    pub fn double<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &Ed25519Affine,
+    ) -> Ed25519Affine {
+        let xy = self.curve_f.mul(native, &p.x, &p.y);
+        let xx = self.curve_f.mul(native, &p.x, &p.x);
+        let yy = self.curve_f.mul(native, &p.y, &p.y);
+
+        //x3 = 2xy / (y^2 - x^2)
+        let x_num = self.curve_f.add(native, &xy, &xy);
+        let x_den = self.curve_f.sub(native, &yy, &xx);
+        let x3 = self.curve_f.div(native, &x_num, &x_den);
+
+        //y3 = (y^2 + x^2) / (2 - y^2 + x^2)
+        let y_num = self.curve_f.add(native, &yy, &xx);
+        let two = value_of::<C, B, Curve25519Fp>(native, Box::new(2));
+        let y_den = self.curve_f.sub(native, &two, &x_den);
+        let y3 = self.curve_f.div(native, &y_num, &y_den);
+
+        Ed25519Affine::new(x3, y3)
+    }
+
+    pub fn select<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        selector: Variable,
+        p: &Ed25519Affine,
+        q: &Ed25519Affine,
+    ) -> Ed25519Affine {
+        let x = self.curve_f.select(native, selector, &p.x, &q.x);
+        let y = self.curve_f.select(native, selector, &p.y, &q.y);
+        Ed25519Affine::new(x, y)
+    }
+
+    pub fn assert_is_equal<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &Ed25519Affine,
+        q: &Ed25519Affine,
+    ) {
+        self.curve_f.assert_is_equal(native, &p.x, &q.x);
+        self.curve_f.assert_is_equal(native, &p.y, &q.y);
+    }
+
+    // scalar_mul_bits computes [s]p for s given as little-endian bits
+    pub fn scalar_mul_bits<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        p: &Ed25519Affine,
+        bits: &[Variable],
+    ) -> Ed25519Affine {
+        let mut res = self.identity();
+        let mut acc = p.clone();
+        for (i, bit) in bits.iter().enumerate() {
+            let sum = self.add(native, &res, &acc);
+            res = self.select(native, *bit, &sum, &res);
+            if i + 1 < bits.len() {
+                acc = self.double(native, &acc);
+            }
+        }
+        res
+    }
+
+    // base_mul_bits computes [s]B for s given as little-endian bits. The multiples 2^i*B are
+    // computed out of circuit, so only the additions are paid for.
+    pub fn base_mul_bits<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        bits: &[Variable],
+    ) -> Ed25519Affine {
+        let mut res = self.identity();
+        let mut acc = (
+            BigInt::from_str(BASE_X).unwrap(),
+            BigInt::from_str(BASE_Y).unwrap(),
+        );
+        for (i, bit) in bits.iter().enumerate() {
+            let x = value_of::<C, B, Curve25519Fp>(native, Box::new(acc.0.clone()));
+            let y = value_of::<C, B, Curve25519Fp>(native, Box::new(acc.1.clone()));
+            let sum = self.add(native, &res, &Ed25519Affine::new(x, y));
+            res = self.select(native, *bit, &sum, &res);
+            if i + 1 < bits.len() {
+                acc = native::add(&acc, &acc);
+            }
+        }
+        res
+    }
+
+    // msm computes sum_i [s_i]p_i for s_i given as little-endian bits, of any lengths. It is the
+    // Straus method with MSM_WINDOW-bit windows: one accumulator walks down the windows of the
+    // longest scalar, so the doublings are shared by all points, and each point adds the entry
+    // of its table picked by the current window. The law is complete, so the tables start at
+    // the identity and zero windows need no special case. For n points and scalars of up to
+    // b bits this costs 4 * (ceil(b / 4) - 1) doublings and n * (14 + ceil(b / 4)) additions,
+    // against b doublings and b additions per point for scalar_mul_bits.
+    pub fn msm<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        points: &[Ed25519Affine],
+        scalars: &[Vec<Variable>],
+    ) -> Ed25519Affine {
+        assert_eq!(points.len(), scalars.len());
+        let mut tables = vec![];
+        for p in points.iter() {
+            let mut table = vec![self.identity(), p.clone()];
+            for j in 2..1 << MSM_WINDOW {
+                let entry = self.add(native, &table[j - 1], p);
+                table.push(entry);
+            }
+            tables.push(table);
+        }
+        let windows = scalars
+            .iter()
+            .map(|bits| bits.chunks(MSM_WINDOW).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let len = windows.iter().map(|w| w.len()).max().unwrap();
+
+        let mut res = self.identity();
+        for i in (0..len).rev() {
+            if i + 1 < len {
+                for _ in 0..MSM_WINDOW {
+                    res = self.double(native, &res);
+                }
+            }
+            for (table, windows) in tables.iter().zip(windows.iter()) {
+                if i < windows.len() {
+                    let entry = self.lookup(native, table, windows[i]);
+                    res = self.add(native, &res, &entry);
+                }
+            }
+        }
+        res
+    }
+
+    // lookup returns table[j] for j given as little-endian bits, folding the table bit by bit
+    fn lookup<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        table: &[Ed25519Affine],
+        bits: &[Variable],
+    ) -> Ed25519Affine {
+        let mut layer = table[..1 << bits.len()].to_vec();
+        for bit in bits.iter() {
+            layer = layer
+                .chunks(2)
+                .map(|pair| self.select(native, *bit, &pair[1], &pair[0]))
+                .collect();
+        }
+        layer.pop().unwrap()
+    }
+
+    // decompress decodes the RFC 8032 encoding and rejects anything decompress_checked flags
+    pub fn decompress<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        bytes: &[Variable],
+    ) -> Ed25519Affine {
+        let (p, is_valid) = self.decompress_checked(native, bytes);
+        let one = native.constant(1);
+        native.assert_is_equal(is_valid, one);
+        p
+    }
+
+    // decompress_checked decodes y in little-endian with the sign of x in the top bit, as in
+    // RFC 8032 5.1.3. is_valid is 1 only for the canonical encoding of a curve point:
+    // - y < p
+    // - (y^2 - 1) / (d*y^2 + 1) is a square
+    // - x = 0 comes with a zero sign bit
+    // When is_valid is 0 the returned point is meaningless.
+    pub fn decompress_checked<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        bytes: &[Variable],
+    ) -> (Ed25519Affine, Variable) {
+        let mut buf_y = vec![];
+        for byte in bytes[..31].iter() {
+            to_binary(native, *byte, 8);
+            buf_y.push(*byte);
+        }
+        let top = to_binary(native, bytes[31], 8);
+        let sign = top[7];
+        buf_y.push(from_binary(native, top[..7].to_vec()));
+        let y = new_internal_element::<Curve25519Fp>(buf_y, 0);
+
+        //y < p
+        let modulus = value_of::<C, B, Curve25519Fp>(native, Box::new(Curve25519Fp::modulus()));
+        let y_is_canonical = big_less_than(
+            native,
+            Curve25519Fp::bits_per_limb() as usize,
+            Curve25519Fp::nb_limbs() as usize,
+            &y.limbs,
+            &modulus.limbs,
+        );
+
+        //x^2 = (y^2 - 1) / (d*y^2 + 1), the denominator never vanishes since -1/d is not a square
+        let yy = self.curve_f.mul(native, &y, &y);
+        let one = self.curve_f.one_const.clone();
+        let u = self.curve_f.sub(native, &yy, &one);
+        let dyy = self.curve_f.mul(native, &self.d, &yy);
+        let v = self.curve_f.add(native, &dyy, &one);
+        let (is_square, x) = self.curve_f.sqrt_ratio(native, &u, &v);
+
+        //x = 0 has no negative, so a set sign bit is a second encoding of the same point
+        let x_is_zero = self.curve_f.is_zero(native, &x);
+        let negative_zero = native.mul(x_is_zero, sign);
+
+        //pick the root whose parity matches the sign bit
+        let x_sign = self.curve_f.get_element_sign(native, &x);
+        let sign_diff = native.sub(x_sign, sign);
+        let keep = native.is_zero(sign_diff);
+        let neg_x = self.curve_f.neg(native, &x);
+        let x = self.curve_f.select(native, keep, &x, &neg_x);
+
+        let is_valid = native.mul(y_is_canonical, is_square);
+        let one_var = native.constant(1);
+        let not_negative_zero = native.sub(one_var, negative_zero);
+        let is_valid = native.mul(is_valid, not_negative_zero);
+
+        (Ed25519Affine::new(x, y), is_valid)
+    }
+}
+
+declare_circuit!(Ed25519DecompressCircuit {
+    bytes: [Variable; 32],
+    is_valid: Variable,
+});
+
+impl GenericDefine<M31Config> for Ed25519DecompressCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut ed = Ed25519::new(builder);
+        let (_, is_valid) = ed.decompress_checked(builder, &self.bytes);
+        builder.assert_is_equal(is_valid, self.is_valid);
+        ed.curve_f.final_check(builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ed25519DecompressCircuit;
+    use crate::ed25519::register_ed25519_hint;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+    use num_bigint::BigInt;
+    use num_traits::Num;
+
+    fn run(encoding: &str, is_valid: u32) {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_ed25519_hint(&mut hint_registry);
+        let mut assignment = Ed25519DecompressCircuit::<M31> {
+            bytes: [M31::from(0); 32],
+            is_valid: M31::from(is_valid),
+        };
+        //encodings are written as little-endian hex strings, like RFC 8032
+        let bytes = BigInt::from_str_radix(encoding, 16).unwrap().to_bytes_be().1;
+        let bytes = [vec![0u8; 32 - bytes.len()], bytes].concat();
+        for i in 0..32 {
+            assignment.bytes[i] = M31::from(bytes[i] as u32);
+        }
+        debug_eval(&Ed25519DecompressCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    fn test_ed25519_decompress() {
+        run("03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8", 1);
+        //identity, y = 1
+        run("0100000000000000000000000000000000000000000000000000000000000000", 1);
+    }
+
+    #[test]
+    fn test_ed25519_decompress_flags_non_canonical() {
+        //y = p, a second encoding of y = 0
+        run("edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f", 0);
+        //y = 1 with the sign bit set, a second encoding of the identity
+        run("0100000000000000000000000000000000000000000000000000000000000080", 0);
+        //y = 2 is not the y-coordinate of any point
+        run("0200000000000000000000000000000000000000000000000000000000000000", 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ed25519_decompress_rejects_wrong_flag() {
+        run("0200000000000000000000000000000000000000000000000000000000000000", 1);
+    }
+}
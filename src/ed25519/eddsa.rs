@@ -0,0 +1,146 @@
+use circuit_std_rs::gnark::element::*;
+use expander_compiler::{
+    declare_circuit,
+    frontend::{Config, GenericDefine, M31Config, RootAPI, Variable, M31},
+};
+
+use crate::ed25519::curve::*;
+use crate::ed25519::scalar::*;
+use crate::hash::sha512::sha512_var;
+
+// capacity of the msg input, msg_len selects how many of its bytes are signed
+pub const ED25519_MAX_MSG_LEN: usize = 128;
+
+// challenge computes k = SHA-512(R || A || M) mod L for the first msg_len bytes of msg
+pub fn challenge<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    scalar_f: &mut Ed25519ScalarF,
+    r_bytes: &[Variable],
+    pub_key: &[Variable],
+    msg: &[Variable],
+    msg_len: Variable,
+) -> Element<Ed25519Scalar> {
+    let k_input = [r_bytes, pub_key, msg].concat();
+    let prefix_len = native.constant(64);
+    let k_len = native.add(prefix_len, msg_len);
+    let k_bytes = sha512_var(native, &k_input, k_len);
+    scalar_f.reduce_wide(native, &k_bytes)
+}
+
+declare_circuit!(Ed25519VerifyCircuit {
+    pub_key: [Variable; 32],
+    sig: [Variable; 64],
+    msg: [Variable; ED25519_MAX_MSG_LEN],
+    msg_len: Variable,
+});
+
+impl GenericDefine<M31Config> for Ed25519VerifyCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut ed = Ed25519::new(builder);
+        let mut scalar_f = Ed25519ScalarF::new(builder);
+        let a = ed.decompress(builder, &self.pub_key);
+        let r = ed.decompress(builder, &self.sig[..32]);
+
+        //S < L, otherwise (R, S + L) would be a second valid signature
+        let s = new_internal_element::<Ed25519Scalar>(self.sig[32..].to_vec(), 0);
+        scalar_f.assert_is_canonical(builder, &s.limbs);
+        let s_bits = scalar_f.to_bits(builder, &s);
+
+        let k = challenge(
+            builder,
+            &mut scalar_f,
+            &self.sig[..32],
+            &self.pub_key,
+            &self.msg,
+            self.msg_len,
+        );
+        let k_bits = scalar_f.to_bits(builder, &k);
+
+        //[8]([S]B - R - [k]A) = O, the cofactored equation of RFC 8032
+        let sb = ed.base_mul_bits(builder, &s_bits);
+        let ka = ed.scalar_mul_bits(builder, &a, &k_bits);
+        let r_ka = ed.add(builder, &r, &ka);
+        let r_ka_neg = ed.neg(builder, &r_ka);
+        let mut diff = ed.add(builder, &sb, &r_ka_neg);
+        for _ in 0..3 {
+            diff = ed.double(builder, &diff);
+        }
+        let identity = ed.identity();
+        ed.assert_is_equal(builder, &diff, &identity);
+
+        ed.curve_f.final_check(builder);
+        scalar_f.final_check(builder);
+    }
+}
+
+// reference_assignment is the RFC 8032 signature of 0x56..56 under the key derived from the seed
+// 00 01 .. 1f
+pub fn reference_assignment() -> Ed25519VerifyCircuit<M31> {
+    let mut assignment = Ed25519VerifyCircuit::<M31> {
+        pub_key: [M31::from(0); 32],
+        sig: [M31::from(0); 64],
+        msg: [M31::from(0); ED25519_MAX_MSG_LEN],
+        msg_len: M31::from(32),
+    };
+    for i in 0..32 {
+        assignment.msg[i] = M31::from(0x56);
+    }
+    let pub_key: [u8; 32] = [
+        0x03, 0xa1, 0x07, 0xbf, 0xf3, 0xce, 0x10, 0xbe, 0x1d, 0x70, 0xdd, 0x18, 0xe7, 0x4b, 0xc0,
+        0x99, 0x67, 0xe4, 0xd6, 0x30, 0x9b, 0xa5, 0x0d, 0x5f, 0x1d, 0xdc, 0x86, 0x64, 0x12, 0x55,
+        0x31, 0xb8,
+    ];
+    let sig: [u8; 64] = [
+        0xf0, 0x8f, 0x41, 0xc8, 0xc3, 0x2d, 0xcf, 0x4b, 0x6b, 0x2f, 0x10, 0x29, 0xcc, 0xeb, 0x8a,
+        0xd7, 0x2c, 0xc3, 0x2f, 0xf7, 0xd4, 0xe3, 0x18, 0xc3, 0x47, 0x3e, 0x78, 0xa6, 0x18, 0xa8,
+        0x3d, 0xc8, 0x9f, 0xbc, 0x08, 0x19, 0xec, 0xa8, 0x31, 0x38, 0x00, 0x84, 0x00, 0x8e, 0xb3,
+        0x57, 0xac, 0x89, 0xfe, 0x52, 0x98, 0x32, 0x7a, 0x2e, 0x59, 0x13, 0xdc, 0x34, 0x94, 0xd3,
+        0x7f, 0xdf, 0x6f, 0x0b,
+    ];
+    for i in 0..32 {
+        assignment.pub_key[i] = M31::from(pub_key[i] as u32);
+    }
+    for i in 0..64 {
+        assignment.sig[i] = M31::from(sig[i] as u32);
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reference_assignment, Ed25519VerifyCircuit};
+    use crate::ed25519::register_ed25519_hint;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    #[test]
+    fn test_ed25519_verify() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_ed25519_hint(&mut hint_registry);
+        debug_eval(&Ed25519VerifyCircuit::default(), &reference_assignment(), hint_registry);
+    }
+
+    #[test]
+    fn test_ed25519_verify_ignores_bytes_past_msg_len() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_ed25519_hint(&mut hint_registry);
+        let mut assignment = reference_assignment();
+        assignment.msg[100] = M31::from(0x57);
+        debug_eval(&Ed25519VerifyCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ed25519_verify_rejects_tampered_message() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_ed25519_hint(&mut hint_registry);
+        let mut assignment = reference_assignment();
+        assignment.msg[0] = M31::from(0x57);
+        debug_eval(&Ed25519VerifyCircuit::default(), &assignment, hint_registry);
+    }
+}
@@ -0,0 +1,274 @@
+use circuit_std_rs::gnark::element::*;
+use circuit_std_rs::gnark::emparam::FieldParams;
+use circuit_std_rs::gnark::field::GField;
+use circuit_std_rs::gnark::hints::unwrap_hint;
+use expander_compiler::{
+    declare_circuit,
+    frontend::{Config, Error, GenericDefine, M31Config, RootAPI, Variable, M31},
+};
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+// sqrt(-1) = 2^((p-1)/4) mod p
+const SQRT_M1: &str =
+    "19681161376707505956807079304988542015446066515923890162744021073123829784752";
+
+// Curve25519Fp emulates GF(2^255 - 19) with 32 limbs of 8 bits, the same limb width as Bls12381Fp.
+// - the limbs are exactly the bytes of the RFC 8032 little-endian encoding, so encoded points
+//   become elements without any recomposition
+// - a schoolbook limb product column sums at most 32 products of 16 bits, i.e. < 2^21, which is
+//   far below the M31 modulus and leaves room for the carries of the deferred mul check
+// - all range checks go through the same 8-bit lookup table as the BLS12-381 chips
+#[derive(Default, Clone, Copy)]
+pub struct Curve25519Fp {}
+
+impl FieldParams for Curve25519Fp {
+    fn nb_limbs() -> u32 {
+        32
+    }
+    fn bits_per_limb() -> u32 {
+        8
+    }
+    fn is_prime() -> bool {
+        true
+    }
+    fn modulus() -> BigInt {
+        (BigInt::one() << 255) - 19
+    }
+}
+
+// Curve25519F is the field chip for Curve25519Fp. It exposes the same operations as CurveF and
+// adds the square root gadgets needed to decode Edwards points.
+pub struct Curve25519F {
+    pub field: GField<Curve25519Fp>,
+    pub zero_const: Element<Curve25519Fp>,
+    pub one_const: Element<Curve25519Fp>,
+    pub sqrt_m1: Element<Curve25519Fp>,
+}
+
+impl Curve25519F {
+    pub fn new<C: Config, B: RootAPI<C>>(native: &mut B) -> Self {
+        let field = GField::new(native, Curve25519Fp {});
+        let zero_const = value_of::<C, B, Curve25519Fp>(native, Box::new(0));
+        let one_const = field.one_const.clone();
+        let sqrt_m1 = value_of::<C, B, Curve25519Fp>(native, Box::new(SQRT_M1.to_string()));
+        Self {
+            field,
+            zero_const,
+            one_const,
+            sqrt_m1,
+        }
+    }
+
+    pub fn add<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+        b: &Element<Curve25519Fp>,
+    ) -> Element<Curve25519Fp> {
+        self.field.add(native, a, b)
+    }
+    pub fn sub<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+        b: &Element<Curve25519Fp>,
+    ) -> Element<Curve25519Fp> {
+        self.field.sub(native, a, b)
+    }
+    pub fn neg<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+    ) -> Element<Curve25519Fp> {
+        self.field.neg(native, a)
+    }
+    pub fn mul<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+        b: &Element<Curve25519Fp>,
+    ) -> Element<Curve25519Fp> {
+        self.field.mul(native, a, b)
+    }
+    pub fn div<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+        b: &Element<Curve25519Fp>,
+    ) -> Element<Curve25519Fp> {
+        self.field.div(native, a, b)
+    }
+    pub fn inverse<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+    ) -> Element<Curve25519Fp> {
+        self.field.inverse(native, a)
+    }
+    // select returns a if selector is 1 and b otherwise
+    pub fn select<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        selector: Variable,
+        a: &Element<Curve25519Fp>,
+        b: &Element<Curve25519Fp>,
+    ) -> Element<Curve25519Fp> {
+        self.field.select(native, selector, a, b)
+    }
+    pub fn is_zero<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+    ) -> Variable {
+        self.field.is_zero(native, a)
+    }
+    pub fn assert_is_equal<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+        b: &Element<Curve25519Fp>,
+    ) {
+        self.field.assert_is_equal(native, a, b)
+    }
+    // get_element_sign returns the parity of the reduced element, the "sign" of RFC 8032
+    pub fn get_element_sign<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+    ) -> Variable {
+        self.field.get_element_sign(native, a)
+    }
+
+    // sqrt_ratio returns (is_square, x). When u/v is a square, is_square = 1 and x^2 * v = u.
+    // Otherwise is_square = 0 and x^2 * v = sqrt(-1) * u, which is a witness of non-squareness
+    // because sqrt(-1) is itself a non-residue for p = 5 mod 8. v must be non-zero.
+    pub fn sqrt_ratio<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        u: &Element<Curve25519Fp>,
+        v: &Element<Curve25519Fp>,
+    ) -> (Variable, Element<Curve25519Fp>) {
+        let inputs = vec![u.my_clone(), v.my_clone()];
+        let outputs = self
+            .field
+            .new_hint(native, "myhint.ed25519sqrtratiohint", 2, inputs);
+        let is_square_hint = outputs[0].clone();
+        let x = outputs[1].clone();
+
+        let non_square = self.field.is_zero(native, &is_square_hint);
+        let one = native.constant(1);
+        let is_square = native.sub(one, non_square);
+
+        //u = 0 is always a square, so a prover cannot claim otherwise
+        let u_is_zero = self.field.is_zero(native, u);
+        let bad_claim = native.mul(u_is_zero, non_square);
+        native.assert_is_zero(bad_claim);
+
+        let xx = self.field.mul(native, &x, &x);
+        let xxv = self.field.mul(native, &xx, v);
+        let iu = self.field.mul(native, &self.sqrt_m1, u);
+        let expected = self.field.select(native, is_square, u, &iu);
+        self.field.assert_is_equal(native, &xxv, &expected);
+        (is_square, x)
+    }
+
+    // sqrt returns (is_square, x) with x^2 = a whenever a is a square
+    pub fn sqrt<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Curve25519Fp>,
+    ) -> (Variable, Element<Curve25519Fp>) {
+        let one = self.one_const.clone();
+        self.sqrt_ratio(native, a, &one)
+    }
+
+    pub fn check_mul<C: Config, B: RootAPI<C>>(&mut self, native: &mut B) {
+        self.field.check_mul(native);
+    }
+
+    // final_check flushes the deferred multiplication checks and closes the range-check table.
+    // It must run once, after every other gadget of the circuit.
+    pub fn final_check<C: Config, B: RootAPI<C>>(&mut self, native: &mut B) {
+        self.field.check_mul(native);
+        self.field.table.final_check(native);
+        self.field.table.final_check(native);
+        self.field.table.final_check(native);
+    }
+}
+
+// ed25519_sqrt_ratio_hint returns (1, sqrt(u/v)) when u/v is a square and
+// (0, sqrt(sqrt(-1) * u/v)) otherwise
+pub fn ed25519_sqrt_ratio_hint(inputs: &[M31], outputs: &mut [M31]) -> Result<(), Error> {
+    if let Err(err) = unwrap_hint(true, true, inputs, outputs, |inputs| {
+        let modulus = Curve25519Fp::modulus();
+        let sqrt_m1: BigInt = SQRT_M1.parse().unwrap();
+        let u = &inputs[0] % &modulus;
+        let v = &inputs[1] % &modulus;
+        let ratio = u * v.modpow(&(&modulus - 2), &modulus) % &modulus;
+        let (is_square, ratio) = if ratio.modpow(&((&modulus - 1) / 2), &modulus) <= BigInt::one()
+        {
+            (BigInt::one(), ratio)
+        } else {
+            (BigInt::zero(), ratio * &sqrt_m1 % &modulus)
+        };
+        let mut x = ratio.modpow(&((&modulus + 3) / 8), &modulus);
+        if (&x * &x - &ratio) % &modulus != BigInt::zero() {
+            x = x * &sqrt_m1 % &modulus;
+        }
+        Ok(vec![is_square, x])
+    }) {
+        panic!("ed25519_sqrt_ratio_hint: {}", err);
+    }
+    Ok(())
+}
+
+declare_circuit!(Curve25519SqrtCircuit {
+    a: [Variable; 32],
+    is_square: Variable,
+});
+
+impl GenericDefine<M31Config> for Curve25519SqrtCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut curve_f = Curve25519F::new(builder);
+        let a = new_internal_element(self.a.to_vec(), 0);
+        let (is_square, _) = curve_f.sqrt(builder, &a);
+        builder.assert_is_equal(is_square, self.is_square);
+        curve_f.final_check(builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Curve25519SqrtCircuit;
+    use crate::ed25519::register_ed25519_hint;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    fn run(a: u32, is_square: u32) {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_ed25519_hint(&mut hint_registry);
+        let mut assignment = Curve25519SqrtCircuit::<M31> {
+            a: [M31::from(0); 32],
+            is_square: M31::from(is_square),
+        };
+        assignment.a[0] = M31::from(a);
+        debug_eval(&Curve25519SqrtCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    fn test_curve25519_sqrt() {
+        run(4, 1);
+        //2 is a non-residue since p = 5 mod 8
+        run(2, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_curve25519_sqrt_rejects_wrong_flag() {
+        run(2, 1);
+    }
+}
@@ -0,0 +1,13 @@
+use expander_compiler::frontend::{HintRegistry, M31};
+
+pub mod batch;
+pub mod curve;
+pub mod eddsa;
+pub mod field;
+pub mod native;
+pub mod scalar;
+
+pub fn register_ed25519_hint(hint_registry: &mut HintRegistry<M31>) {
+    hint_registry.register("myhint.ed25519sqrtratiohint", field::ed25519_sqrt_ratio_hint);
+    hint_registry.register("myhint.ed25519scalarreducehint", scalar::ed25519_scalar_reduce_hint);
+}
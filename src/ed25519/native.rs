@@ -0,0 +1,129 @@
+use std::str::FromStr;
+
+use circuit_std_rs::gnark::emparam::FieldParams;
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Zero};
+use sha2::{Digest, Sha512};
+
+use crate::ed25519::curve::{BASE_X, BASE_Y, D};
+use crate::ed25519::field::Curve25519Fp;
+use crate::ed25519::scalar::Ed25519Scalar;
+
+// Points in extended coordinates (X : Y : Z : T) with x = X/Z, y = Y/Z and xy = T/Z, as in
+// RFC 8032 5.1.4, so a scalar multiplication pays for a single inversion
+type Extended = (BigInt, BigInt, BigInt, BigInt);
+
+fn reduce(v: &BigInt, modulus: &BigInt) -> BigInt {
+    ((v % modulus) + modulus) % modulus
+}
+
+fn to_extended(p: &(BigInt, BigInt)) -> Extended {
+    let modulus = Curve25519Fp::modulus();
+    (p.0.clone(), p.1.clone(), BigInt::one(), &p.0 * &p.1 % &modulus)
+}
+
+fn to_affine(p: &Extended) -> (BigInt, BigInt) {
+    let modulus = Curve25519Fp::modulus();
+    let z = reduce(&p.2, &modulus);
+    let z_inv = z.modpow(&(&modulus - 2), &modulus);
+    (reduce(&(&p.0 * &z_inv), &modulus), reduce(&(&p.1 * &z_inv), &modulus))
+}
+
+fn add_extended(p: &Extended, q: &Extended) -> Extended {
+    let modulus = Curve25519Fp::modulus();
+    let d2 = BigInt::from_str(D).unwrap() * 2;
+    let a = (&p.1 - &p.0) * (&q.1 - &q.0) % &modulus;
+    let b = (&p.1 + &p.0) * (&q.1 + &q.0) % &modulus;
+    let c = &p.3 * &d2 * &q.3 % &modulus;
+    let d = &p.2 * 2 * &q.2 % &modulus;
+    let (e, f, g, h) = (&b - &a, &d - &c, &d + &c, &b + &a);
+    (
+        &e * &f % &modulus,
+        &g * &h % &modulus,
+        &f * &g % &modulus,
+        &e * &h % &modulus,
+    )
+}
+
+// add is the unified addition law of Ed25519::add on affine points
+pub fn add(p: &(BigInt, BigInt), q: &(BigInt, BigInt)) -> (BigInt, BigInt) {
+    to_affine(&add_extended(&to_extended(p), &to_extended(q)))
+}
+
+// base_mul returns [s]B for s >= 0
+pub fn base_mul(s: &BigInt) -> (BigInt, BigInt) {
+    let base = to_extended(&(
+        BigInt::from_str(BASE_X).unwrap(),
+        BigInt::from_str(BASE_Y).unwrap(),
+    ));
+    let mut res = (BigInt::zero(), BigInt::one(), BigInt::one(), BigInt::zero());
+    for i in (0..s.bits()).rev() {
+        res = add_extended(&res, &res);
+        if s.bit(i) {
+            res = add_extended(&res, &base);
+        }
+    }
+    to_affine(&res)
+}
+
+// compress encodes y in little-endian with the sign of x in the top bit, as in RFC 8032 5.1.2
+pub fn compress(p: &(BigInt, BigInt)) -> [u8; 32] {
+    let mut bytes = to_bytes_le(&p.1);
+    if p.0.bit(0) {
+        bytes[31] |= 0x80;
+    }
+    bytes
+}
+
+fn to_bytes_le(v: &BigInt) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let le = v.to_bytes_le().1;
+    bytes[..le.len()].copy_from_slice(&le);
+    bytes
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> BigInt {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    BigInt::from_bytes_le(Sign::Plus, &hasher.finalize()) % Ed25519Scalar::modulus()
+}
+
+// sign returns the RFC 8032 public key of seed and its signature of msg
+pub fn sign(seed: &[u8; 32], msg: &[u8]) -> ([u8; 32], [u8; 64]) {
+    let h = Sha512::digest(seed);
+    let mut a = [0u8; 32];
+    a.copy_from_slice(&h[..32]);
+    a[0] &= 248;
+    a[31] &= 127;
+    a[31] |= 64;
+    let a = BigInt::from_bytes_le(Sign::Plus, &a);
+    let pub_key = compress(&base_mul(&a));
+
+    let r = hash_to_scalar(&[&h[32..], msg]);
+    let r_bytes = compress(&base_mul(&r));
+    let k = hash_to_scalar(&[&r_bytes[..], &pub_key[..], msg]);
+    let s = (r + k * a) % Ed25519Scalar::modulus();
+
+    let mut sig = [0u8; 64];
+    sig[..32].copy_from_slice(&r_bytes);
+    sig[32..].copy_from_slice(&to_bytes_le(&s));
+    (pub_key, sig)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sign;
+    use crate::ed25519::eddsa::reference_assignment;
+    use expander_compiler::frontend::M31;
+
+    #[test]
+    fn test_sign_matches_reference() {
+        let seed: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let (pub_key, sig) = sign(&seed, &[0x56; 32]);
+        let expected = reference_assignment();
+        assert_eq!(pub_key.map(|b| M31::from(b as u32)), expected.pub_key);
+        assert_eq!(sig.map(|b| M31::from(b as u32)), expected.sig);
+    }
+}
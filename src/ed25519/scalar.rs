@@ -0,0 +1,139 @@
+use circuit_std_rs::big_int::*;
+use circuit_std_rs::gnark::element::*;
+use circuit_std_rs::gnark::emparam::FieldParams;
+use circuit_std_rs::gnark::field::GField;
+use circuit_std_rs::gnark::hints::unwrap_hint;
+use expander_compiler::frontend::{Config, Error, RootAPI, Variable, M31};
+use num_bigint::BigInt;
+use num_traits::One;
+
+// 2^256 mod L, used to fold the high half of a 512-bit hash
+const TWO_256_MOD_L: &str =
+    "7237005577332262213973186563042994240413239274941949949428319933631315875101";
+
+// Ed25519Scalar emulates the prime order subgroup scalar field, L = 2^252 + 27742317777372353535851937790883648493.
+// It uses the same 32 x 8-bit layout as Curve25519Fp so S and hash bytes map straight to limbs.
+#[derive(Default, Clone, Copy)]
+pub struct Ed25519Scalar {}
+
+impl FieldParams for Ed25519Scalar {
+    fn nb_limbs() -> u32 {
+        32
+    }
+    fn bits_per_limb() -> u32 {
+        8
+    }
+    fn is_prime() -> bool {
+        true
+    }
+    fn modulus() -> BigInt {
+        (BigInt::one() << 252) + "27742317777372353535851937790883648493".parse::<BigInt>().unwrap()
+    }
+}
+
+pub struct Ed25519ScalarF {
+    pub field: GField<Ed25519Scalar>,
+}
+
+impl Ed25519ScalarF {
+    pub fn new<C: Config, B: RootAPI<C>>(native: &mut B) -> Self {
+        let field = GField::new(native, Ed25519Scalar {});
+        Self { field }
+    }
+
+    // assert_is_canonical checks that little-endian byte limbs encode an integer below L
+    pub fn assert_is_canonical<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        limbs: &Vec<Variable>,
+    ) {
+        let l = value_of::<C, B, Ed25519Scalar>(native, Box::new(Ed25519Scalar::modulus()));
+        let is_reduced = big_less_than(
+            native,
+            Ed25519Scalar::bits_per_limb() as usize,
+            Ed25519Scalar::nb_limbs() as usize,
+            limbs,
+            &l.limbs,
+        );
+        let one = native.constant(1);
+        native.assert_is_equal(is_reduced, one);
+    }
+
+    // reduce returns the canonical residue of a mod L. It comes from a hint and is pinned by
+    // equality mod L and the bound r < L.
+    pub fn reduce<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Ed25519Scalar>,
+    ) -> Element<Ed25519Scalar> {
+        let outputs = self
+            .field
+            .new_hint(native, "myhint.ed25519scalarreducehint", 1, vec![a.my_clone()]);
+        let r = outputs[0].clone();
+        self.field.assert_is_equal(native, &r, a);
+        self.assert_is_canonical(native, &r.limbs);
+        r
+    }
+
+    // reduce_wide returns k mod L for a 64-byte little-endian k, folding the high half with 2^256 mod L
+    pub fn reduce_wide<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        bytes: &[Variable],
+    ) -> Element<Ed25519Scalar> {
+        let lo = new_internal_element::<Ed25519Scalar>(bytes[..32].to_vec(), 0);
+        let hi = new_internal_element::<Ed25519Scalar>(bytes[32..].to_vec(), 0);
+        let shift = value_of::<C, B, Ed25519Scalar>(native, Box::new(TWO_256_MOD_L.to_string()));
+        let hi_shifted = self.field.mul(native, &hi, &shift);
+        let k = self.field.add(native, &lo, &hi_shifted);
+        self.reduce(native, &k)
+    }
+
+    pub fn mul<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Ed25519Scalar>,
+        b: &Element<Ed25519Scalar>,
+    ) -> Element<Ed25519Scalar> {
+        self.field.mul(native, a, b)
+    }
+
+    pub fn add<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        a: &Element<Ed25519Scalar>,
+        b: &Element<Ed25519Scalar>,
+    ) -> Element<Ed25519Scalar> {
+        self.field.add(native, a, b)
+    }
+
+    // to_bits returns the 253 little-endian bits of a canonical scalar
+    pub fn to_bits<C: Config, B: RootAPI<C>>(
+        &mut self,
+        native: &mut B,
+        r: &Element<Ed25519Scalar>,
+    ) -> Vec<Variable> {
+        let mut bits = vec![];
+        for limb in r.limbs.iter() {
+            bits.extend(to_binary(native, *limb, 8));
+        }
+        bits.truncate(253);
+        bits
+    }
+
+    pub fn final_check<C: Config, B: RootAPI<C>>(&mut self, native: &mut B) {
+        self.field.check_mul(native);
+        self.field.table.final_check(native);
+        self.field.table.final_check(native);
+        self.field.table.final_check(native);
+    }
+}
+
+pub fn ed25519_scalar_reduce_hint(inputs: &[M31], outputs: &mut [M31]) -> Result<(), Error> {
+    if let Err(err) = unwrap_hint(true, true, inputs, outputs, |inputs| {
+        Ok(vec![&inputs[0] % Ed25519Scalar::modulus()])
+    }) {
+        panic!("ed25519_scalar_reduce_hint: {}", err);
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::hash::concat_var;
+use crate::hash::sha256::{sha256, sha256_var};
+use circuit_std_rs::big_int::*;
+use expander_compiler::frontend::{Config, RootAPI, Variable};
+
+// expand_message_xmd of RFC 9380 5.3.1 instantiated with SHA-256. The message length, the DST
+// and len_in_bytes are all fixed when the circuit is built.
+pub fn expand_message_xmd<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    msg: &[Variable],
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> Vec<Variable> {
+    let dst_prime = dst_prime(native, dst);
+    let z_pad = vec![native.constant(0); 64];
+    let suffix = b_0_suffix(native, &dst_prime, len_in_bytes);
+
+    //b_0 = H(Z_pad || msg || l_i_b_str || 0 || DST_prime)
+    let b_0 = sha256(native, &[&z_pad[..], msg, &suffix].concat());
+    expand_from_b_0(native, &b_0, &dst_prime, len_in_bytes)
+}
+
+// expand_message_xmd_var is expand_message_xmd over the first msg_len bytes of msg
+pub fn expand_message_xmd_var<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    msg: &[Variable],
+    msg_len: Variable,
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> Vec<Variable> {
+    let dst_prime = dst_prime(native, dst);
+    let z_pad = vec![native.constant(0); 64];
+    let suffix = b_0_suffix(native, &dst_prime, len_in_bytes);
+
+    //the suffix has to follow the message directly, so it is spliced in at msg_len
+    let body = concat_var(native, msg, msg_len, &suffix);
+    let prefix_len = native.constant(64 + suffix.len() as u32);
+    let b_0_len = native.add(prefix_len, msg_len);
+    let b_0 = sha256_var(native, &[z_pad, body].concat(), b_0_len);
+    expand_from_b_0(native, &b_0, &dst_prime, len_in_bytes)
+}
+
+fn dst_prime<C: Config, B: RootAPI<C>>(native: &mut B, dst: &[u8]) -> Vec<Variable> {
+    assert!(dst.len() <= 255);
+    dst.iter()
+        .chain([dst.len() as u8].iter())
+        .map(|b| native.constant(*b as u32))
+        .collect()
+}
+
+// b_0_suffix is l_i_b_str || 0 || DST_prime
+fn b_0_suffix<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    dst_prime: &[Variable],
+    len_in_bytes: usize,
+) -> Vec<Variable> {
+    assert!(len_in_bytes.div_ceil(32) <= 255 && len_in_bytes <= 65535);
+    let mut suffix = vec![
+        native.constant((len_in_bytes >> 8) as u32),
+        native.constant((len_in_bytes & 0xff) as u32),
+        native.constant(0),
+    ];
+    suffix.extend_from_slice(dst_prime);
+    suffix
+}
+
+//b_1 = H(b_0 || 1 || DST_prime), b_i = H((b_0 xor b_(i-1)) || i || DST_prime)
+fn expand_from_b_0<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    b_0: &[Variable],
+    dst_prime: &[Variable],
+    len_in_bytes: usize,
+) -> Vec<Variable> {
+    let ell = len_in_bytes.div_ceil(32);
+    let mut uniform_bytes = vec![];
+    let mut b_i = vec![];
+    for i in 1..=ell {
+        let mut input = if i == 1 {
+            b_0.to_vec()
+        } else {
+            strxor(native, b_0, &b_i)
+        };
+        input.push(native.constant(i as u32));
+        input.extend_from_slice(dst_prime);
+        b_i = sha256(native, &input);
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+fn strxor<C: Config, B: RootAPI<C>>(native: &mut B, a: &[Variable], b: &[Variable]) -> Vec<Variable> {
+    let mut res = vec![];
+    for (x, y) in a.iter().zip(b.iter()) {
+        let x_bits = to_binary(native, *x, 8);
+        let y_bits = to_binary(native, *y, 8);
+        let bits = x_bits
+            .iter()
+            .zip(y_bits.iter())
+            .map(|(xb, yb)| native.xor(*xb, *yb))
+            .collect::<Vec<_>>();
+        res.push(from_binary(native, bits));
+    }
+    res
+}
@@ -0,0 +1,260 @@
+pub mod expand_message;
+pub mod sha256;
+pub mod sha512;
+
+use circuit_std_rs::big_int::*;
+use expander_compiler::frontend::{Config, RootAPI, Variable};
+
+// The SHA-2 gadgets work on words stored as bit vectors, least significant bit first. Rotations
+// and shifts are then free re-indexings, the boolean functions cost one or two products per bit,
+// and modular additions recompose 16-bit chunks so that every intermediate sum stays far below
+// the M31 modulus.
+
+pub fn constant_word<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    value: u64,
+    width: usize,
+) -> Vec<Variable> {
+    (0..width)
+        .map(|i| native.constant(((value >> i) & 1) as u32))
+        .collect()
+}
+
+// word_from_bytes_be packs big-endian bytes into a word, range checking every byte on the way
+pub fn word_from_bytes_be<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    bytes: &[Variable],
+) -> Vec<Variable> {
+    let mut word = vec![];
+    for byte in bytes.iter().rev() {
+        word.extend(to_binary(native, *byte, 8));
+    }
+    word
+}
+
+pub fn word_to_bytes_be<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    word: &[Variable],
+) -> Vec<Variable> {
+    word.chunks(8)
+        .rev()
+        .map(|bits| from_binary(native, bits.to_vec()))
+        .collect()
+}
+
+pub fn rotr(word: &[Variable], n: usize) -> Vec<Variable> {
+    let width = word.len();
+    (0..width).map(|i| word[(i + n) % width]).collect()
+}
+
+pub fn shr<C: Config, B: RootAPI<C>>(native: &mut B, word: &[Variable], n: usize) -> Vec<Variable> {
+    let width = word.len();
+    let zero = native.constant(0);
+    (0..width)
+        .map(|i| if i + n < width { word[i + n] } else { zero })
+        .collect()
+}
+
+pub fn xor3<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    a: &[Variable],
+    b: &[Variable],
+    c: &[Variable],
+) -> Vec<Variable> {
+    let mut res = vec![];
+    for i in 0..a.len() {
+        let ab = native.xor(a[i], b[i]);
+        res.push(native.xor(ab, c[i]));
+    }
+    res
+}
+
+// ch(e, f, g) = (e & f) ^ (!e & g) = g + e * (f - g)
+pub fn ch<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    e: &[Variable],
+    f: &[Variable],
+    g: &[Variable],
+) -> Vec<Variable> {
+    let mut res = vec![];
+    for i in 0..e.len() {
+        let fg = native.sub(f[i], g[i]);
+        let efg = native.mul(e[i], fg);
+        res.push(native.add(efg, g[i]));
+    }
+    res
+}
+
+// maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c) = a * b + c * (a ^ b)
+pub fn maj<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    a: &[Variable],
+    b: &[Variable],
+    c: &[Variable],
+) -> Vec<Variable> {
+    let mut res = vec![];
+    for i in 0..a.len() {
+        let ab = native.mul(a[i], b[i]);
+        let a_xor_b = native.xor(a[i], b[i]);
+        let c_xor = native.mul(c[i], a_xor_b);
+        res.push(native.add(ab, c_xor));
+    }
+    res
+}
+
+// add_words sums words modulo 2^width. Each 16-bit column is summed natively together with the
+// incoming carry, then split back into 16 result bits and the carry for the next column.
+pub fn add_words<C: Config, B: RootAPI<C>>(native: &mut B, words: &[Vec<Variable>]) -> Vec<Variable> {
+    let width = words[0].len();
+    let carry_bits = (usize::BITS - (words.len() - 1).leading_zeros()) as usize;
+    let mut carry = native.constant(0);
+    let mut res = vec![];
+    for chunk in 0..width / 16 {
+        let mut sum = carry;
+        for word in words {
+            let limb = from_binary(native, word[chunk * 16..(chunk + 1) * 16].to_vec());
+            sum = native.add(sum, limb);
+        }
+        let sum_bits = to_binary(native, sum, 16 + carry_bits);
+        res.extend_from_slice(&sum_bits[..16]);
+        carry = from_binary(native, sum_bits[16..].to_vec());
+    }
+    res
+}
+
+// pad_var lays out the Merkle-Damgard padding of the first len bytes of data, for blocks of
+// block_len bytes that end with a len_field-byte big-endian bit length. Every block that a
+// message of data.len() bytes could need is emitted. Alongside the bytes it returns one flag per
+// block, set only on the block that holds the length field, i.e. the last one that counts.
+pub fn pad_var<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    data: &[Variable],
+    len: Variable,
+    block_len: usize,
+    len_field: usize,
+) -> (Vec<Variable>, Vec<Variable>) {
+    let capacity = data.len();
+    let nb_blocks = (capacity + len_field) / block_len + 1;
+    let tail_start = block_len - len_field;
+
+    //8*len as big-endian bytes, also bounding len below 2^21
+    let zero = native.constant(0);
+    let len_bits = to_binary(native, len, 21);
+    let bit_len = [vec![zero; 3], len_bits, vec![zero; len_field * 8 - 24]].concat();
+    let bit_len_bytes = word_to_bytes_be(native, &bit_len);
+
+    //is_end[i] = 1 iff i == len, and exactly one i in 0..=capacity must match
+    let is_end = position_flags(native, len, capacity + 1);
+    let one = native.constant(1);
+    let marker_byte = native.constant(0x80);
+    let mut seen = zero;
+    let mut padded = vec![];
+    for i in 0..nb_blocks * block_len {
+        let mut byte = zero;
+        if i <= capacity {
+            seen = native.add(seen, is_end[i]);
+        }
+        if i < capacity {
+            //data[i] is kept while i < len
+            let is_data = native.sub(one, seen);
+            byte = native.mul(is_data, data[i]);
+        }
+        if i <= capacity {
+            let marker = native.mul(is_end[i], marker_byte);
+            byte = native.add(byte, marker);
+        }
+        padded.push(byte);
+    }
+    native.assert_is_equal(seen, one);
+
+    //block b is the last one iff the 0x80 marker sits in [b*block_len - len_field, b*block_len + tail_start)
+    let mut is_last = vec![];
+    for b in 0..nb_blocks {
+        let mut flag = zero;
+        for i in (b * block_len).saturating_sub(len_field)..b * block_len + tail_start {
+            if i <= capacity {
+                flag = native.add(flag, is_end[i]);
+            }
+        }
+        is_last.push(flag);
+    }
+    for b in 0..nb_blocks {
+        for (j, len_byte) in bit_len_bytes.iter().enumerate() {
+            let i = b * block_len + tail_start + j;
+            let term = native.mul(is_last[b], *len_byte);
+            padded[i] = native.add(padded[i], term);
+        }
+    }
+    (padded, is_last)
+}
+
+// select_state returns the chaining value of the block flagged in is_last
+pub fn select_state<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    states: &[Vec<Vec<Variable>>],
+    is_last: &[Variable],
+) -> Vec<Vec<Variable>> {
+    let zero = native.constant(0);
+    let mut res = vec![vec![zero; states[0][0].len()]; states[0].len()];
+    for (state, flag) in states.iter().zip(is_last.iter()) {
+        for (acc, word) in res.iter_mut().zip(state.iter()) {
+            for (acc_bit, bit) in acc.iter_mut().zip(word.iter()) {
+                let term = native.mul(*flag, *bit);
+                *acc_bit = native.add(*acc_bit, term);
+            }
+        }
+    }
+    res
+}
+
+// concat_var returns head[..len] || tail, zero-filled to head.len() + tail.len() bytes.
+// len must be at most head.len().
+pub fn concat_var<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    head: &[Variable],
+    len: Variable,
+    tail: &[Variable],
+) -> Vec<Variable> {
+    let is_end = position_flags(native, len, head.len() + 1);
+    let zero = native.constant(0);
+    let one = native.constant(1);
+    let mut seen = zero;
+    let mut res = vec![];
+    for i in 0..head.len() + tail.len() {
+        let mut byte = zero;
+        if i < head.len() {
+            seen = native.add(seen, is_end[i]);
+            let is_head = native.sub(one, seen);
+            byte = native.mul(is_head, head[i]);
+        }
+        //tail[k] lands at len + k
+        for (k, tail_byte) in tail.iter().enumerate() {
+            if i >= k && i - k <= head.len() {
+                let term = native.mul(is_end[i - k], *tail_byte);
+                byte = native.add(byte, term);
+            }
+        }
+        res.push(byte);
+    }
+    let mut total = zero;
+    for flag in is_end.iter() {
+        total = native.add(total, *flag);
+    }
+    native.assert_is_equal(total, one);
+    res
+}
+
+// position_flags returns n flags, the i-th of which is 1 iff pos == i
+fn position_flags<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    pos: Variable,
+    n: usize,
+) -> Vec<Variable> {
+    let mut flags = vec![];
+    for i in 0..n {
+        let i_var = native.constant(i as u32);
+        let diff = native.sub(pos, i_var);
+        flags.push(native.is_zero(diff));
+    }
+    flags
+}
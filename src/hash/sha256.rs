@@ -0,0 +1,119 @@
+use super::*;
+use expander_compiler::frontend::{Config, RootAPI, Variable};
+
+const IV: [u64; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const K: [u64; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// sha256 hashes a byte string whose length is fixed when the circuit is built. Returns the 32
+// digest bytes.
+pub fn sha256<C: Config, B: RootAPI<C>>(native: &mut B, data: &[Variable]) -> Vec<Variable> {
+    let mut padded = data.to_vec();
+    padded.push(native.constant(0x80));
+    while (padded.len() + 8) % 64 != 0 {
+        padded.push(native.constant(0));
+    }
+    let bit_len = data.len() as u64 * 8;
+    for byte in bit_len.to_be_bytes() {
+        padded.push(native.constant(byte as u32));
+    }
+
+    let mut state: Vec<Vec<Variable>> = IV.iter().map(|v| constant_word(native, *v, 32)).collect();
+    for block in padded.chunks(64) {
+        state = compress(native, &state, block);
+    }
+    let mut digest = vec![];
+    for word in state.iter() {
+        digest.extend(word_to_bytes_be(native, word));
+    }
+    digest
+}
+
+// sha256_var hashes the first len bytes of data, see sha512_var
+pub fn sha256_var<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    data: &[Variable],
+    len: Variable,
+) -> Vec<Variable> {
+    let (padded, is_last) = pad_var(native, data, len, 64, 8);
+    let mut state: Vec<Vec<Variable>> = IV.iter().map(|v| constant_word(native, *v, 32)).collect();
+    let mut states = vec![];
+    for block in padded.chunks(64) {
+        state = compress(native, &state, block);
+        states.push(state.clone());
+    }
+    let state = select_state(native, &states, &is_last);
+    let mut digest = vec![];
+    for word in state.iter() {
+        digest.extend(word_to_bytes_be(native, word));
+    }
+    digest
+}
+
+fn compress<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    state: &[Vec<Variable>],
+    block: &[Variable],
+) -> Vec<Vec<Variable>> {
+    let mut w: Vec<Vec<Variable>> = block
+        .chunks(4)
+        .map(|bytes| word_from_bytes_be(native, bytes))
+        .collect();
+    for t in 16..64 {
+        let s1_shr = shr(native, &w[t - 2], 10);
+        let s1 = xor3(native, &rotr(&w[t - 2], 17), &rotr(&w[t - 2], 19), &s1_shr);
+        let s0_shr = shr(native, &w[t - 15], 3);
+        let s0 = xor3(native, &rotr(&w[t - 15], 7), &rotr(&w[t - 15], 18), &s0_shr);
+        let wt = add_words(native, &[s1, w[t - 7].clone(), s0, w[t - 16].clone()]);
+        w.push(wt);
+    }
+
+    let mut a = state[0].clone();
+    let mut b = state[1].clone();
+    let mut c = state[2].clone();
+    let mut d = state[3].clone();
+    let mut e = state[4].clone();
+    let mut f = state[5].clone();
+    let mut g = state[6].clone();
+    let mut h = state[7].clone();
+    for (t, wt) in w.iter().enumerate() {
+        let sigma1 = xor3(native, &rotr(&e, 6), &rotr(&e, 11), &rotr(&e, 25));
+        let ch = ch(native, &e, &f, &g);
+        let kt = constant_word(native, K[t], 32);
+        let sigma0 = xor3(native, &rotr(&a, 2), &rotr(&a, 13), &rotr(&a, 22));
+        let maj = maj(native, &a, &b, &c);
+
+        //e' = d + T1, a' = T1 + T2 with T1 = h + Σ1(e) + Ch(e, f, g) + K[t] + W[t], T2 = Σ0(a) + Maj(a, b, c)
+        let new_e = add_words(
+            native,
+            &[d, h.clone(), sigma1.clone(), ch.clone(), kt.clone(), wt.clone()],
+        );
+        let new_a = add_words(native, &[h, sigma1, ch, kt, wt.clone(), sigma0, maj]);
+        h = g;
+        g = f;
+        f = e;
+        e = new_e;
+        d = c;
+        c = b;
+        b = a;
+        a = new_a;
+    }
+
+    let working = [a, b, c, d, e, f, g, h];
+    state
+        .iter()
+        .zip(working)
+        .map(|(s, v)| add_words(native, &[s.clone(), v]))
+        .collect()
+}
@@ -0,0 +1,134 @@
+use super::*;
+use expander_compiler::frontend::{Config, RootAPI, Variable};
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+const K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+// sha512 hashes a byte string whose length is fixed when the circuit is built, so the padding
+// is laid out at compile time. Returns the 64 digest bytes.
+pub fn sha512<C: Config, B: RootAPI<C>>(native: &mut B, data: &[Variable]) -> Vec<Variable> {
+    let mut padded = data.to_vec();
+    padded.push(native.constant(0x80));
+    while (padded.len() + 16) % 128 != 0 {
+        padded.push(native.constant(0));
+    }
+    let bit_len = data.len() as u128 * 8;
+    for byte in bit_len.to_be_bytes() {
+        padded.push(native.constant(byte as u32));
+    }
+
+    let mut state: Vec<Vec<Variable>> = IV.iter().map(|v| constant_word(native, *v, 64)).collect();
+    for block in padded.chunks(128) {
+        state = compress(native, &state, block);
+    }
+    let mut digest = vec![];
+    for word in state.iter() {
+        digest.extend(word_to_bytes_be(native, word));
+    }
+    digest
+}
+
+// sha512_var hashes the first len bytes of data, where len is a witness. data.len() is the
+// capacity; every block that a message of that capacity could need is compressed and the digest
+// is taken from the block that holds the length field.
+pub fn sha512_var<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    data: &[Variable],
+    len: Variable,
+) -> Vec<Variable> {
+    let (padded, is_last) = pad_var(native, data, len, 128, 16);
+    let mut state: Vec<Vec<Variable>> = IV.iter().map(|v| constant_word(native, *v, 64)).collect();
+    let mut states = vec![];
+    for block in padded.chunks(128) {
+        state = compress(native, &state, block);
+        states.push(state.clone());
+    }
+    let state = select_state(native, &states, &is_last);
+    let mut digest = vec![];
+    for word in state.iter() {
+        digest.extend(word_to_bytes_be(native, word));
+    }
+    digest
+}
+
+fn compress<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    state: &[Vec<Variable>],
+    block: &[Variable],
+) -> Vec<Vec<Variable>> {
+    let mut w: Vec<Vec<Variable>> = block
+        .chunks(8)
+        .map(|bytes| word_from_bytes_be(native, bytes))
+        .collect();
+    for t in 16..80 {
+        let s1_shr = shr(native, &w[t - 2], 6);
+        let s1 = xor3(native, &rotr(&w[t - 2], 19), &rotr(&w[t - 2], 61), &s1_shr);
+        let s0_shr = shr(native, &w[t - 15], 7);
+        let s0 = xor3(native, &rotr(&w[t - 15], 1), &rotr(&w[t - 15], 8), &s0_shr);
+        let wt = add_words(native, &[s1, w[t - 7].clone(), s0, w[t - 16].clone()]);
+        w.push(wt);
+    }
+
+    let mut a = state[0].clone();
+    let mut b = state[1].clone();
+    let mut c = state[2].clone();
+    let mut d = state[3].clone();
+    let mut e = state[4].clone();
+    let mut f = state[5].clone();
+    let mut g = state[6].clone();
+    let mut h = state[7].clone();
+    for (t, wt) in w.iter().enumerate() {
+        let sigma1 = xor3(native, &rotr(&e, 14), &rotr(&e, 18), &rotr(&e, 41));
+        let ch = ch(native, &e, &f, &g);
+        let kt = constant_word(native, K[t], 64);
+        let sigma0 = xor3(native, &rotr(&a, 28), &rotr(&a, 34), &rotr(&a, 39));
+        let maj = maj(native, &a, &b, &c);
+
+        //e' = d + T1, a' = T1 + T2 with T1 = h + Σ1(e) + Ch(e, f, g) + K[t] + W[t], T2 = Σ0(a) + Maj(a, b, c)
+        let new_e = add_words(
+            native,
+            &[d, h.clone(), sigma1.clone(), ch.clone(), kt.clone(), wt.clone()],
+        );
+        let new_a = add_words(native, &[h, sigma1, ch, kt, wt.clone(), sigma0, maj]);
+        h = g;
+        g = f;
+        f = e;
+        e = new_e;
+        d = c;
+        c = b;
+        b = a;
+        a = new_a;
+    }
+
+    let working = [a, b, c, d, e, f, g, h];
+    state
+        .iter()
+        .zip(working)
+        .map(|(s, v)| add_words(native, &[s.clone(), v]))
+        .collect()
+}
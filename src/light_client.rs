@@ -0,0 +1,541 @@
+use ark_bls12_381::{Fr, G1Affine as NativeG1};
+use ark_ec::{AffineRepr, CurveGroup};
+use circuit_std_rs::big_int::to_binary;
+use expander_compiler::{
+    declare_circuit,
+    frontend::{Config, GenericDefine, M31Config, PublicVariable, RootAPI, Variable, M31},
+};
+
+use crate::bls12_381::{ciphersuite::Ciphersuite, g1::*, g2::*, native, pairing::Pairing};
+use crate::ssz::*;
+
+pub const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+
+// sync committee signatures use the proof-of-possession scheme of the beacon chain
+pub const LIGHT_CLIENT_CIPHERSUITE: Ciphersuite = Ciphersuite::G2Pop;
+
+// Mainnet genesis_validators_root and the Deneb fork version, used by the reference update
+const GENESIS_VALIDATORS_ROOT: &str =
+    "4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95";
+const FORK_VERSION: [u8; 4] = [4, 0, 0, 0];
+
+// Position of next_sync_committee in the Deneb BeaconState tree
+pub const NEXT_SYNC_COMMITTEE_GINDEX: usize = 55;
+pub const NEXT_SYNC_COMMITTEE_DEPTH: usize = 5;
+
+declare_circuit!(LightClientUpdateCircuit {
+    // attested BeaconBlockHeader, integers as SSZ little-endian bytes
+    slot: [Variable; 8],
+    proposer_index: [Variable; 8],
+    parent_root: [Variable; 32],
+    state_root: [Variable; 32],
+    body_root: [Variable; 32],
+    sync_committee_pubkeys: [[Variable; 48]; SYNC_COMMITTEE_SIZE],
+    sync_committee_aggregate_pubkey: [Variable; 48],
+    sync_committee_bits: [Variable; SYNC_COMMITTEE_SIZE],
+    sync_committee_signature: [Variable; 96],
+    fork_version: [PublicVariable; 4],
+    genesis_validators_root: [PublicVariable; 32],
+    sync_committee_root: [PublicVariable; 32],
+    attested_header_root: [PublicVariable; 32],
+});
+
+// Verifies the sync aggregate of a light client update (altair/light-client/sync-protocol.md):
+// the participating members of the committee behind sync_committee_root signed the signing root
+// of the attested header, and they are a supermajority of the committee.
+impl GenericDefine<M31Config> for LightClientUpdateCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut pairing = Pairing::new(builder);
+        let mut g1 = G1::new(builder);
+        let mut g2 = G2::new(builder);
+
+        let (pub_keys, committee_root) = decode_sync_committee(
+            builder,
+            &mut g1,
+            &self.sync_committee_pubkeys,
+            &self.sync_committee_aggregate_pubkey,
+        );
+        assert_bytes_equal(builder, &committee_root, &self.sync_committee_root);
+
+        let (agg_pk, participant_count) =
+            aggregate_participants(builder, &mut g1, &pub_keys, &self.sync_committee_bits);
+        assert_supermajority(builder, participant_count, SYNC_COMMITTEE_SIZE);
+
+        let header_root = beacon_block_header_root(
+            builder,
+            &self.slot,
+            &self.proposer_index,
+            &self.parent_root,
+            &self.state_root,
+            &self.body_root,
+        );
+        assert_bytes_equal(builder, &header_root, &self.attested_header_root);
+
+        let domain = compute_domain(
+            builder,
+            DOMAIN_SYNC_COMMITTEE,
+            &self.fork_version,
+            &self.genesis_validators_root,
+        );
+        let signing_root = compute_signing_root(builder, &header_root, &domain);
+        let msg_g2 = g2.hash_to_g2(builder, &signing_root, LIGHT_CLIENT_CIPHERSUITE);
+
+        let sig = g2.uncompressed(builder, &self.sync_committee_signature);
+        let g1_gen = G1Affine::one(builder);
+        let g1_neg = g1.neg(builder, &g1_gen);
+        pairing
+            .pairing_check(
+                builder,
+                &[g1_neg, agg_pk],
+                &mut [
+                    G2Affine {
+                        p: sig,
+                        lines: LineEvaluations::default(),
+                    },
+                    G2Affine {
+                        p: msg_g2,
+                        lines: LineEvaluations::default(),
+                    },
+                ],
+            )
+            .unwrap();
+
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.check_mul(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+declare_circuit!(LightClientRotationCircuit {
+    slot: [Variable; 8],
+    proposer_index: [Variable; 8],
+    parent_root: [Variable; 32],
+    state_root: [Variable; 32],
+    body_root: [Variable; 32],
+    sync_committee_pubkeys: [[Variable; 48]; SYNC_COMMITTEE_SIZE],
+    sync_committee_aggregate_pubkey: [Variable; 48],
+    sync_committee_bits: [Variable; SYNC_COMMITTEE_SIZE],
+    sync_committee_signature: [Variable; 96],
+    next_sync_committee_branch: [[Variable; 32]; NEXT_SYNC_COMMITTEE_DEPTH],
+    fork_version: [PublicVariable; 4],
+    genesis_validators_root: [PublicVariable; 32],
+    sync_committee_root: [PublicVariable; 32],
+    attested_header_root: [PublicVariable; 32],
+    next_sync_committee_root: [PublicVariable; 32],
+});
+
+// A rotation proof is an update proof that also shows next_sync_committee_root is committed to by
+// the state root of the attested header. Its next_sync_committee_root is the sync_committee_root
+// of the proof for the following period, which chains proofs from a trusted committee onwards.
+impl GenericDefine<M31Config> for LightClientRotationCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let state_root = merkle_branch_root(
+            builder,
+            &self.next_sync_committee_root,
+            &self.next_sync_committee_branch,
+            NEXT_SYNC_COMMITTEE_GINDEX - (1 << NEXT_SYNC_COMMITTEE_DEPTH),
+        );
+        assert_bytes_equal(builder, &state_root, &self.state_root);
+
+        let update = LightClientUpdateCircuit::<Variable> {
+            slot: self.slot,
+            proposer_index: self.proposer_index,
+            parent_root: self.parent_root,
+            state_root: self.state_root,
+            body_root: self.body_root,
+            sync_committee_pubkeys: self.sync_committee_pubkeys,
+            sync_committee_aggregate_pubkey: self.sync_committee_aggregate_pubkey,
+            sync_committee_bits: self.sync_committee_bits,
+            sync_committee_signature: self.sync_committee_signature,
+            fork_version: self.fork_version,
+            genesis_validators_root: self.genesis_validators_root,
+            sync_committee_root: self.sync_committee_root,
+            attested_header_root: self.attested_header_root,
+        };
+        update.define(builder);
+    }
+}
+
+// aggregate_participants sums the keys whose bit is set with G1::sum_selected and returns the
+// sum with the number of participants, which must be non-zero
+pub fn aggregate_participants<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    g1: &mut G1,
+    pub_keys: &[G1Affine],
+    bits: &[Variable],
+) -> (G1Affine, Variable) {
+    let mut count = native.constant(0);
+    for bit in bits.iter() {
+        native.assert_is_bool(*bit);
+        count = native.add(count, *bit);
+    }
+    native.assert_is_non_zero(count);
+    let agg_pk = g1.sum_selected(native, pub_keys, bits);
+    (agg_pk, count)
+}
+
+// assert_supermajority checks count * 3 >= size * 2, i.e. that the difference is a small
+// non-negative number rather than a wrapped-around field element
+pub fn assert_supermajority<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    count: Variable,
+    size: usize,
+) {
+    let three = native.constant(3);
+    let lhs = native.mul(count, three);
+    let rhs = native.constant(2 * size as u32);
+    let margin = native.sub(lhs, rhs);
+    let bits = (size + 1).next_power_of_two().trailing_zeros() as usize;
+    to_binary(native, margin, bits);
+}
+
+pub fn assert_bytes_equal<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    a: &[Variable],
+    b: &[Variable],
+) {
+    for (x, y) in a.iter().zip(b.iter()) {
+        native.assert_is_equal(*x, *y);
+    }
+}
+
+fn committee_pubkeys(sks: &[Fr]) -> Vec<Vec<u8>> {
+    sks.iter()
+        .map(|sk| native::compress_g1(&(NativeG1::generator() * sk).into_affine()))
+        .collect()
+}
+
+fn committee_aggregate_pubkey(sks: &[Fr]) -> Vec<u8> {
+    let sk_sum: Fr = sks.iter().sum();
+    native::compress_g1(&(NativeG1::generator() * sk_sum).into_affine())
+}
+
+fn committee_root(sks: &[Fr]) -> Vec<u8> {
+    native_sync_committee_root(&committee_pubkeys(sks), &committee_aggregate_pubkey(sks))
+}
+
+fn bytes_to_m31(bytes: &[u8], out: &mut [M31]) {
+    for (limb, byte) in out.iter_mut().zip(bytes.iter()) {
+        *limb = M31::from(*byte as u32);
+    }
+}
+
+// ReferenceUpdate is a light client update signed by a synthetic committee in which member i
+// holds the key 0x5eed + i. Every eighth member does not participate.
+pub struct ReferenceUpdate {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: Vec<u8>,
+    pub state_root: Vec<u8>,
+    pub body_root: Vec<u8>,
+    pub fork_version: Vec<u8>,
+    pub genesis_validators_root: Vec<u8>,
+    pub committee_sks: Vec<Fr>,
+    pub participation: Vec<bool>,
+}
+
+impl ReferenceUpdate {
+    pub fn new() -> Self {
+        Self {
+            slot: 9_000_000,
+            proposer_index: 42,
+            parent_root: vec![1; 32],
+            state_root: vec![2; 32],
+            body_root: vec![3; 32],
+            fork_version: FORK_VERSION.to_vec(),
+            genesis_validators_root: (0..32)
+                .map(|i| u8::from_str_radix(&GENESIS_VALIDATORS_ROOT[2 * i..2 * i + 2], 16).unwrap())
+                .collect(),
+            committee_sks: (0..SYNC_COMMITTEE_SIZE)
+                .map(|i| Fr::from(0x5eed + i as u64))
+                .collect(),
+            participation: (0..SYNC_COMMITTEE_SIZE).map(|i| i % 8 != 0).collect(),
+        }
+    }
+
+    pub fn committee_pubkeys(&self) -> Vec<Vec<u8>> {
+        committee_pubkeys(&self.committee_sks)
+    }
+
+    pub fn committee_aggregate_pubkey(&self) -> Vec<u8> {
+        committee_aggregate_pubkey(&self.committee_sks)
+    }
+
+    pub fn committee_root(&self) -> Vec<u8> {
+        committee_root(&self.committee_sks)
+    }
+
+    pub fn header_root(&self) -> Vec<u8> {
+        native_beacon_block_header_root(
+            self.slot,
+            self.proposer_index,
+            &self.parent_root,
+            &self.state_root,
+            &self.body_root,
+        )
+    }
+
+    pub fn signature(&self) -> Vec<u8> {
+        let domain = native_compute_domain(
+            DOMAIN_SYNC_COMMITTEE,
+            &self.fork_version,
+            &self.genesis_validators_root,
+        );
+        let signing_root = native_hash_pair(&self.header_root(), &domain);
+        let sk_sum: Fr = self
+            .committee_sks
+            .iter()
+            .zip(self.participation.iter())
+            .filter(|(_, bit)| **bit)
+            .map(|(sk, _)| *sk)
+            .sum();
+        let h = native::hash_to_g2(&signing_root, LIGHT_CLIENT_CIPHERSUITE.dst().unwrap());
+        native::compress_g2(&(h * sk_sum).into_affine())
+    }
+
+    pub fn assignment(&self) -> LightClientUpdateCircuit<M31> {
+        let mut assignment = LightClientUpdateCircuit::<M31> {
+            slot: [M31::from(0); 8],
+            proposer_index: [M31::from(0); 8],
+            parent_root: [M31::from(0); 32],
+            state_root: [M31::from(0); 32],
+            body_root: [M31::from(0); 32],
+            sync_committee_pubkeys: [[M31::from(0); 48]; SYNC_COMMITTEE_SIZE],
+            sync_committee_aggregate_pubkey: [M31::from(0); 48],
+            sync_committee_bits: [M31::from(0); SYNC_COMMITTEE_SIZE],
+            sync_committee_signature: [M31::from(0); 96],
+            fork_version: [M31::from(0); 4],
+            genesis_validators_root: [M31::from(0); 32],
+            sync_committee_root: [M31::from(0); 32],
+            attested_header_root: [M31::from(0); 32],
+        };
+        bytes_to_m31(&self.slot.to_le_bytes(), &mut assignment.slot);
+        bytes_to_m31(&self.proposer_index.to_le_bytes(), &mut assignment.proposer_index);
+        bytes_to_m31(&self.parent_root, &mut assignment.parent_root);
+        bytes_to_m31(&self.state_root, &mut assignment.state_root);
+        bytes_to_m31(&self.body_root, &mut assignment.body_root);
+        for (limbs, pk) in assignment
+            .sync_committee_pubkeys
+            .iter_mut()
+            .zip(self.committee_pubkeys())
+        {
+            bytes_to_m31(&pk, limbs);
+        }
+        bytes_to_m31(
+            &self.committee_aggregate_pubkey(),
+            &mut assignment.sync_committee_aggregate_pubkey,
+        );
+        for (limb, bit) in assignment
+            .sync_committee_bits
+            .iter_mut()
+            .zip(self.participation.iter())
+        {
+            *limb = M31::from(*bit as u32);
+        }
+        bytes_to_m31(&self.signature(), &mut assignment.sync_committee_signature);
+        bytes_to_m31(&self.fork_version, &mut assignment.fork_version);
+        bytes_to_m31(
+            &self.genesis_validators_root,
+            &mut assignment.genesis_validators_root,
+        );
+        bytes_to_m31(&self.committee_root(), &mut assignment.sync_committee_root);
+        bytes_to_m31(&self.header_root(), &mut assignment.attested_header_root);
+        assignment
+    }
+
+    // public_input lists the public inputs in declaration order
+    pub fn public_input(&self) -> Vec<u32> {
+        [
+            self.fork_version.clone(),
+            self.genesis_validators_root.clone(),
+            self.committee_root(),
+            self.header_root(),
+        ]
+        .concat()
+        .iter()
+        .map(|b| *b as u32)
+        .collect()
+    }
+}
+
+impl Default for ReferenceUpdate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ReferenceRotation hands the reference committee over to the committee in which member i holds
+// the key 0x6eed + i. The state root of the attested header is rebuilt from the branch.
+pub struct ReferenceRotation {
+    pub update: ReferenceUpdate,
+    pub next_committee_sks: Vec<Fr>,
+    pub next_sync_committee_branch: Vec<Vec<u8>>,
+}
+
+impl ReferenceRotation {
+    pub fn new() -> Self {
+        let mut update = ReferenceUpdate::new();
+        let next_committee_sks = (0..SYNC_COMMITTEE_SIZE)
+            .map(|i| Fr::from(0x6eed + i as u64))
+            .collect::<Vec<_>>();
+        let next_sync_committee_branch = (0..NEXT_SYNC_COMMITTEE_DEPTH)
+            .map(|i| vec![0x10 + i as u8; 32])
+            .collect::<Vec<_>>();
+        update.state_root = native_merkle_branch_root(
+            &committee_root(&next_committee_sks),
+            &next_sync_committee_branch,
+            NEXT_SYNC_COMMITTEE_GINDEX - (1 << NEXT_SYNC_COMMITTEE_DEPTH),
+        );
+        Self {
+            update,
+            next_committee_sks,
+            next_sync_committee_branch,
+        }
+    }
+
+    pub fn assignment(&self) -> LightClientRotationCircuit<M31> {
+        let update = self.update.assignment();
+        let mut assignment = LightClientRotationCircuit::<M31> {
+            slot: update.slot,
+            proposer_index: update.proposer_index,
+            parent_root: update.parent_root,
+            state_root: update.state_root,
+            body_root: update.body_root,
+            sync_committee_pubkeys: update.sync_committee_pubkeys,
+            sync_committee_aggregate_pubkey: update.sync_committee_aggregate_pubkey,
+            sync_committee_bits: update.sync_committee_bits,
+            sync_committee_signature: update.sync_committee_signature,
+            next_sync_committee_branch: [[M31::from(0); 32]; NEXT_SYNC_COMMITTEE_DEPTH],
+            fork_version: update.fork_version,
+            genesis_validators_root: update.genesis_validators_root,
+            sync_committee_root: update.sync_committee_root,
+            attested_header_root: update.attested_header_root,
+            next_sync_committee_root: [M31::from(0); 32],
+        };
+        for (limbs, node) in assignment
+            .next_sync_committee_branch
+            .iter_mut()
+            .zip(self.next_sync_committee_branch.iter())
+        {
+            bytes_to_m31(node, limbs);
+        }
+        bytes_to_m31(
+            &committee_root(&self.next_committee_sks),
+            &mut assignment.next_sync_committee_root,
+        );
+        assignment
+    }
+
+    pub fn public_input(&self) -> Vec<u32> {
+        let mut public_input = self.update.public_input();
+        public_input.extend(
+            committee_root(&self.next_committee_sks)
+                .iter()
+                .map(|b| *b as u32),
+        );
+        public_input
+    }
+}
+
+impl Default for ReferenceRotation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn light_client_reference_assignment() -> LightClientUpdateCircuit<M31> {
+    ReferenceUpdate::new().assignment()
+}
+
+pub fn light_client_reference_public_input() -> Vec<u32> {
+    ReferenceUpdate::new().public_input()
+}
+
+pub fn rotation_reference_assignment() -> LightClientRotationCircuit<M31> {
+    ReferenceRotation::new().assignment()
+}
+
+pub fn rotation_reference_public_input() -> Vec<u32> {
+    ReferenceRotation::new().public_input()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls12_381::register_bls12381_hint;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    fn run(update: &ReferenceUpdate) {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        debug_eval(
+            &LightClientUpdateCircuit::default(),
+            &update.assignment(),
+            hint_registry,
+        );
+    }
+
+    #[test]
+    fn test_light_client_update() {
+        run(&ReferenceUpdate::new());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_light_client_update_rejects_missing_supermajority() {
+        //the signature matches the participants, but 256 of 512 is not enough
+        let mut update = ReferenceUpdate::new();
+        update.participation = (0..SYNC_COMMITTEE_SIZE).map(|i| i % 2 == 0).collect();
+        run(&update);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_light_client_update_rejects_other_fork() {
+        let update = ReferenceUpdate::new();
+        let mut assignment = update.assignment();
+        assignment.fork_version[0] = M31::from(3);
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        debug_eval(&LightClientUpdateCircuit::default(), &assignment, hint_registry);
+    }
+
+    #[test]
+    fn test_light_client_rotation() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        debug_eval(
+            &LightClientRotationCircuit::default(),
+            &ReferenceRotation::new().assignment(),
+            hint_registry,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_light_client_rotation_rejects_other_next_committee() {
+        let rotation = ReferenceRotation::new();
+        let mut assignment = rotation.assignment();
+        //the committee that signed cannot name itself as its successor
+        assignment.next_sync_committee_root = assignment.sync_committee_root;
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        register_bls12381_hint(&mut hint_registry);
+        debug_eval(&LightClientRotationCircuit::default(), &assignment, hint_registry);
+    }
+}
@@ -1,7 +1,31 @@
 mod bls12_381;
-use crate::bls12_381::{g1::*, g2::*, pairing::Pairing};
+mod ed25519;
+mod hash;
+mod light_client;
+mod ssz;
+use crate::bls12_381::{
+    batch::{bls_batch_reference_assignment, BLSBatchVerifyCircuit},
+    ciphersuite::Ciphersuite,
+    g1::*,
+    g2::*,
+    native,
+    pairing::Pairing,
+    register_bls12381_hint,
+};
+use crate::hash::sha256::sha256;
+use crate::light_client::{
+    aggregate_participants, light_client_reference_assignment,
+    light_client_reference_public_input, rotation_reference_assignment,
+    rotation_reference_public_input, LightClientRotationCircuit, LightClientUpdateCircuit,
+};
+use crate::ed25519::{
+    batch::{batch_reference_assignment, Ed25519BatchVerifyCircuit},
+    eddsa::{reference_assignment, Ed25519VerifyCircuit},
+    register_ed25519_hint,
+};
 use circuit_std_rs::{
-    gnark::{element::Element, emulated::field_bls12381::e2::GE2},
+    big_int::{from_binary, to_binary},
+    gnark::element::Element,
     utils::register_hint,
 };
 use expander_compiler::{
@@ -12,8 +36,6 @@ use gkr::{Prover, Verifier};
 use config::{Config, FiatShamirHashType, GKRConfig, GKRScheme, PolynomialCommitmentType};
 use expander_compiler::{declare_circuit, frontend::*};
 use extra::Serde;
-use num_bigint::BigInt;
-use num_traits::Num;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha12Rng;
 use sha2::Digest;
@@ -21,17 +43,39 @@ use std::{fs, panic::{self, AssertUnwindSafe}, fs::File, io::{BufWriter, Write},
 use circuit::Circuit as GKRCircuit;
 use gkr_field_config::{GKRFieldConfig, M31ExtConfig};
 use mpi_config::{root_println, MPIConfig};
-use arith::Field;
+use arith::{Field, SimdField};
 use config_macros::declare_gkr_config;
 use poly_commit::{expander_pcs_init_testing_only, RawExpanderGKR};
 use transcript::{BytesHashTranscript, Keccak256hasher};
 use serdes::ExpSerde;
+use ark_bls12_381::{Fq, Fq2, Fr, G1Affine as NativeG1, G2Affine as NativeG2};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use std::str::FromStr;
+
+// The reference committee below signs under the tag of curve_f.hash_to_fp. Switch to
+// Ciphersuite::G2Pop to verify Ethereum signatures or Ciphersuite::G2Nul for Filecoin.
+const BLS_CIPHERSUITE: Ciphersuite = Ciphersuite::CircuitStd;
+
+// The min-sig layout hashes to G1. drand quicknet signs under G1Nul.
+const BLS_MIN_SIG_CIPHERSUITE: Ciphersuite = Ciphersuite::G1Nul;
+
+// The committee circuit proves that signers hold at least QUORUM_NUMERATOR / QUORUM_DENOMINATOR
+// of the total weight. Weights are range checked to WEIGHT_BITS, so every weighted sum stays
+// below 2^(WEIGHT_BITS + 9) * QUORUM_DENOMINATOR, far from the M31 modulus.
+const QUORUM_NUMERATOR: u32 = 2;
+const QUORUM_DENOMINATOR: u32 = 3;
+const WEIGHT_BITS: usize = 16;
 
 declare_circuit!(BLSSignatureGKRCircuit {
     g1_gen: [[Variable; 48]; 2],
     pub_keys: [[[Variable; 48]; 2]; 512],
     sigs: [[[[Variable; 48]; 2]; 2]; 512],
-    msg: [Variable; 32],
+    msg: [PublicVariable; 32],
+    participation: [Variable; 512],
+    participant_count: PublicVariable,
+    weights: [Variable; 512],
+    pub_keys_hash: [PublicVariable; 32],
 });
 
 impl GenericDefine<M31Config> for BLSSignatureGKRCircuit<Variable> {
@@ -59,135 +103,75 @@ impl GenericDefine<M31Config> for BLSSignatureGKRCircuit<Variable> {
         let mut g1_impl = G1::new(builder);
         let g1_neg = g1_impl.neg(builder, &g1);
 
-        let mut agg_pk = G1Affine {
-            x: Element::new(
-                self.pub_keys[0][0].to_vec(),
-                0,
-                false,
-                false,
-                false,
-                Variable::default(),
-            ),
-            y: Element::new(
-                self.pub_keys[0][1].to_vec(),
-                0,
-                false,
-                false,
-                false,
-                Variable::default(),
-            ),
-        };
-
-        for i in 1..512 {
-            let _pk = G1Affine {
-                x: Element::new(
-                    self.pub_keys[i][0].to_vec(),
-                    0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-                y: Element::new(
-                    self.pub_keys[i][1].to_vec(),
-                    0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-            };
+        // Keys arrive as raw affine limbs, so each one must be validated before it is summed:
+        // a point outside G1 would let a rogue committee member break the aggregate check.
+        let mut pub_keys = vec![];
+        for i in 0..512 {
+            let pk = G1Affine::from_vars(self.pub_keys[i][0].to_vec(), self.pub_keys[i][1].to_vec());
+            g1_impl.assert_on_curve(builder, &pk);
+            g1_impl.assert_in_subgroup(builder, &pk);
+            pub_keys.push(pk);
+        }
 
-            // Since all public keys are the same in this test, we need to double
-            agg_pk = g1_impl.double(builder, &agg_pk);
+        // pub_keys_hash = SHA-256(x_0 || y_0 || w_0 || .. || x_511 || y_511 || w_511) over
+        // big-endian coordinates and weights makes the proof name the committee and its stake,
+        // so the prover cannot pick the weights. The limbs are little-endian, hence the
+        // reversal, and each weight is range checked to WEIGHT_BITS by its byte decomposition.
+        let mut key_bytes = vec![];
+        for (pk, weight) in self.pub_keys.iter().zip(self.weights.iter()) {
+            key_bytes.extend(pk[0].iter().rev());
+            key_bytes.extend(pk[1].iter().rev());
+            let bits = to_binary(builder, *weight, WEIGHT_BITS);
+            for byte in bits.chunks(8).rev() {
+                key_bytes.push(from_binary(builder, byte.to_vec()));
+            }
+        }
+        let key_hash = sha256(builder, &key_bytes);
+        for (byte, expected) in key_hash.iter().zip(self.pub_keys_hash.iter()) {
+            builder.assert_is_equal(*byte, *expected);
         }
 
-        let mut agg_sig = G2AffP {
-            x: GE2 {
-                a0: Element::new(
-                    self.sigs[0][0][0].to_vec(),
-                    0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-                a1: Element::new(
-                    self.sigs[0][0][1].to_vec(),
-                    0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-            },
-            y: GE2 {
-                a0: Element::new(
-                    self.sigs[0][1][0].to_vec(),
-                    0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-                a1: Element::new(
-                    self.sigs[0][1][1].to_vec(),
-                    0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-            },
-        };
+        // Only participating keys are summed
+        let (agg_pk, participant_count) =
+            aggregate_participants(builder, &mut g1_impl, &pub_keys, &self.participation);
+        builder.assert_is_equal(participant_count, self.participant_count);
+
+        // signed_weight * denom >= total_weight * numer, i.e. the difference is a small
+        // non-negative number rather than a wrapped-around field element
+        let mut total_weight = builder.constant(0);
+        let mut signed_weight = builder.constant(0);
+        for (weight, bit) in self.weights.iter().zip(self.participation.iter()) {
+            total_weight = builder.add(total_weight, *weight);
+            let signed = builder.mul(*weight, *bit);
+            signed_weight = builder.add(signed_weight, signed);
+        }
+        let denom = builder.constant(QUORUM_DENOMINATOR);
+        let numer = builder.constant(QUORUM_NUMERATOR);
+        let lhs = builder.mul(signed_weight, denom);
+        let rhs = builder.mul(total_weight, numer);
+        let margin = builder.sub(lhs, rhs);
+        to_binary(builder, margin, WEIGHT_BITS + 9 + 2);
 
         let mut g2 = G2::new(builder);
 
-        for i in 1..512 {
-            let _sig = G2AffP {
-                x: GE2 {
-                    a0: Element::new(
-                        self.sigs[i][0][0].to_vec(),
-                        0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-                    a1: Element::new(
-                        self.sigs[i][0][1].to_vec(),
-                        0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-                },
-                y: GE2 {
-                    a0: Element::new(
-                        self.sigs[i][1][0].to_vec(),
-                        0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-                    a1: Element::new(
-                        self.sigs[i][1][1].to_vec(),
-                        0,
-                false,
-                false,
-                false,
-                Variable::default(),
-                ),
-                },
-            };
-            // Since all signatures are the same in this test, we need to double
-            agg_sig = g2.g2_double(builder, &agg_sig);
+        // Signatures get the same treatment: the IETF BLS spec requires a subgroup check on
+        // every signature before it reaches the pairing.
+        let mut sigs = vec![];
+        for i in 0..512 {
+            let sig = G2AffP::from_vars(
+                self.sigs[i][0][0].to_vec(),
+                self.sigs[i][0][1].to_vec(),
+                self.sigs[i][1][0].to_vec(),
+                self.sigs[i][1][1].to_vec(),
+            );
+            g2.assert_on_curve(builder, &sig);
+            g2.assert_in_subgroup(builder, &sig);
+            sigs.push(sig);
         }
 
-        let (hm0, hm1) = g2.hash_to_fp(builder, &self.msg);
-        let msg_g2 = g2.map_to_g2(builder, &hm0, &hm1);
+        let agg_sig = g2.sum_selected(builder, &sigs, &self.participation);
+
+        let msg_g2 = g2.hash_to_g2(builder, &self.msg, BLS_CIPHERSUITE);
 
         pairing
             .pairing_check(
@@ -206,6 +190,14 @@ impl GenericDefine<M31Config> for BLSSignatureGKRCircuit<Variable> {
             )
             .unwrap();
 
+        g1_impl.curve_f.check_mul(builder);
+        g1_impl.curve_f.table.final_check(builder);
+        g1_impl.curve_f.table.final_check(builder);
+        g1_impl.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
         pairing.ext12.ext6.ext2.curve_f.check_mul(builder);
         pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
         pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
@@ -213,75 +205,299 @@ impl GenericDefine<M31Config> for BLSSignatureGKRCircuit<Variable> {
     }
 }
 
-#[test]
-fn test_aggregate_pairing_check_gkr() {
-    println!("testing test_aggregate_pairing_check_gkr.....");
-    let mut hint_registry = HintRegistry::<M31>::new();
-    register_hint(&mut hint_registry);
+// BLSMinSigGKRCircuit is the minimal-signature-size layout of BLSSignatureGKRCircuit: keys live
+// in G2, signatures and the hashed message in G1, and the pairing roles are swapped.
+declare_circuit!(BLSMinSigGKRCircuit {
+    pub_keys: [[[[Variable; 48]; 2]; 2]; 512],
+    sigs: [[[Variable; 48]; 2]; 512],
+    msg: [Variable; 32],
+});
+
+impl GenericDefine<M31Config> for BLSMinSigGKRCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut pairing = Pairing::new(builder);
+        let mut g2 = G2::new(builder);
+        let g2_gen = G2AffP::one(builder);
+        let g2_neg = g2.neg(builder, &g2_gen);
+
+        let mut pub_keys = vec![];
+        for i in 0..512 {
+            let pk = G2AffP::from_vars(
+                self.pub_keys[i][0][0].to_vec(),
+                self.pub_keys[i][0][1].to_vec(),
+                self.pub_keys[i][1][0].to_vec(),
+                self.pub_keys[i][1][1].to_vec(),
+            );
+            g2.assert_on_curve(builder, &pk);
+            g2.assert_in_subgroup(builder, &pk);
+            pub_keys.push(pk);
+        }
+
+        let agg_pk = g2.sum(builder, &pub_keys);
+
+        let mut g1_impl = G1::new(builder);
+        let mut sigs = vec![];
+        for i in 0..512 {
+            let sig = G1Affine::from_vars(self.sigs[i][0].to_vec(), self.sigs[i][1].to_vec());
+            g1_impl.assert_on_curve(builder, &sig);
+            g1_impl.assert_in_subgroup(builder, &sig);
+            sigs.push(sig);
+        }
+
+        let agg_sig = g1_impl.sum(builder, &sigs);
+
+        let msg_g1 = g1_impl.hash_to_g1(builder, &self.msg, BLS_MIN_SIG_CIPHERSUITE);
+
+        // e(agg_sig, -g2) * e(H(msg), agg_pk) = 1
+        pairing
+            .pairing_check(
+                builder,
+                &[agg_sig, msg_g1],
+                &mut [
+                    G2Affine {
+                        p: g2_neg,
+                        lines: LineEvaluations::default(),
+                    },
+                    G2Affine {
+                        p: agg_pk,
+                        lines: LineEvaluations::default(),
+                    },
+                ],
+            )
+            .unwrap();
+
+        g1_impl.curve_f.check_mul(builder);
+        g1_impl.curve_f.table.final_check(builder);
+        g1_impl.curve_f.table.final_check(builder);
+        g1_impl.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.check_mul(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        g2.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.check_mul(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+        pairing.ext12.ext6.ext2.curve_f.table.final_check(builder);
+    }
+}
+
+/// Builds a 512-member committee over the reference key pair. Member `i` holds `[2i + 1]pk` and
+/// `[2i + 1]sig`, so the aggregate is still a valid signature over the reference message.
+fn committee_members() -> Vec<(NativeG1, NativeG2)> {
+    // Public key values (original)
+    let pub_key = NativeG1::new_unchecked(
+        Fq::from_str("703326716001809064498853055672224052496326539572945177471956754169145471346922036117906423749397590945884354901914").unwrap(),
+        Fq::from_str("3663539438728798306657207296627492219061036278584663040302088465810128029049191527519011271732625440307576496404164").unwrap(),
+    );
+
+    // Signature values (original)
+    let sig = NativeG2::new_unchecked(
+        Fq2::new(
+            Fq::from_str("1139568035424369576886504746727692711007568237996931740990083083283390253765157354611656848983901259161392255747051").unwrap(),
+            Fq::from_str("1254875381135194965686121199970220725987247367099176854373369962826503465173652424031228084897653264386315457499586").unwrap(),
+        ),
+        Fq2::new(
+            Fq::from_str("3985022954582833583610752589867406089888552465191016224317399257491285114232241437209840121250502955373920952884061").unwrap(),
+            Fq::from_str("249179071186755291707558993402509484460063614865425349660967954952959359540889904597635495866422932058900651130506").unwrap(),
+        ),
+    );
+
+    (0..512)
+        .map(|idx| {
+            let k = Fr::from(2 * idx as u64 + 1);
+            ((pub_key * k).into_affine(), (sig * k).into_affine())
+        })
+        .collect()
+}
+
+/// Member `i` of the reference committee weighs `1 + i % 4`.
+fn committee_weights() -> Vec<u32> {
+    (0..512).map(|i| 1 + i % 4).collect()
+}
+
+/// SHA-256 of the big-endian affine coordinates and weight of every key, as committed to by the
+/// circuit.
+fn committee_key_hash(members: &[(NativeG1, NativeG2)], weights: &[u32]) -> Vec<u8> {
+    let mut hasher = sha2::Sha256::new();
+    for ((pk, _), weight) in members.iter().zip(weights) {
+        hasher.update(pk.x.into_bigint().to_bytes_be());
+        hasher.update(pk.y.into_bigint().to_bytes_be());
+        hasher.update(&weight.to_be_bytes()[4 - WEIGHT_BITS / 8..]);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// The reference committee signs message `0x56..56`. Every member participates with the weight
+/// from `committee_weights`.
+fn committee_assignment() -> BLSSignatureGKRCircuit<M31> {
+    committee_assignment_for(&committee_members())
+}
+
+/// `committee_assignment` over the given members instead of `committee_members()`.
+fn committee_assignment_for(members: &[(NativeG1, NativeG2)]) -> BLSSignatureGKRCircuit<M31> {
     let mut assignment = BLSSignatureGKRCircuit::<M31> {
         g1_gen: [[M31::from(0); 48]; 2],
         pub_keys: [[[M31::from(0); 48]; 2]; 512],
         sigs: [[[[M31::from(0); 48]; 2]; 2]; 512],
-        msg: [M31::from(0); 32],
+        msg: [M31::from(0x56); 32],
+        participation: [M31::from(1); 512],
+        participant_count: M31::from(512),
+        weights: [M31::from(0); 512],
+        pub_keys_hash: [M31::from(0); 32],
     };
+    let weights = committee_weights();
+    for (limb, weight) in assignment.weights.iter_mut().zip(weights.iter()) {
+        *limb = M31::from(*weight);
+    }
 
     // Generator point
-    let g1_a0_bigint = BigInt::from_str_radix("3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507", 10).unwrap();
-    let g1_a1_bigint = BigInt::from_str_radix("1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569", 10).unwrap();
-    let g1_gen_x_bytes = g1_a0_bigint.to_bytes_le();
-    let g1_gen_y_bytes = g1_a1_bigint.to_bytes_le();
-
-    // Public key values (original)
-    let pub_key_a0_bigint = BigInt::from_str_radix("703326716001809064498853055672224052496326539572945177471956754169145471346922036117906423749397590945884354901914", 10).unwrap();
-    let pub_key_a1_bigint = BigInt::from_str_radix("3663539438728798306657207296627492219061036278584663040302088465810128029049191527519011271732625440307576496404164", 10).unwrap();
-    let pub_key_x_bytes = pub_key_a0_bigint.to_bytes_le();
-    let pub_key_y_bytes = pub_key_a1_bigint.to_bytes_le();
+    let g1_gen = NativeG1::generator();
+    native::g1_to_limbs(&g1_gen, &mut assignment.g1_gen);
 
-    // Signature values (original)
-    let sig_b0_a0_bigint = BigInt::from_str_radix("1139568035424369576886504746727692711007568237996931740990083083283390253765157354611656848983901259161392255747051", 10).unwrap();
-    let sig_b0_a1_bigint = BigInt::from_str_radix("1254875381135194965686121199970220725987247367099176854373369962826503465173652424031228084897653264386315457499586", 10).unwrap();
-    let sig_b1_a0_bigint = BigInt::from_str_radix("3985022954582833583610752589867406089888552465191016224317399257491285114232241437209840121250502955373920952884061", 10).unwrap();
-    let sig_b1_a1_bigint = BigInt::from_str_radix("249179071186755291707558993402509484460063614865425349660967954952959359540889904597635495866422932058900651130506", 10).unwrap();
-    let sig_x0_bytes = sig_b0_a0_bigint.to_bytes_le();
-    let sig_x1_bytes = sig_b0_a1_bigint.to_bytes_le();
-    let sig_y0_bytes = sig_b1_a0_bigint.to_bytes_le();
-    let sig_y1_bytes = sig_b1_a1_bigint.to_bytes_le();
-
-    // Initialize generator point
-    for i in 0..48 {
-        assignment.g1_gen[0][i] = M31::from(g1_gen_x_bytes.1[i] as u32);
-        assignment.g1_gen[1][i] = M31::from(g1_gen_y_bytes.1[i] as u32);
+    for (idx, (pk_i, sig_i)) in members.iter().enumerate() {
+        native::g1_to_limbs(pk_i, &mut assignment.pub_keys[idx]);
+        native::g2_to_limbs(sig_i, &mut assignment.sigs[idx]);
+    }
+    let key_hash = committee_key_hash(members, &weights);
+    for (limb, byte) in assignment.pub_keys_hash.iter_mut().zip(key_hash) {
+        *limb = M31::from(byte as u32);
     }
+    assignment
+}
+
+/// The public inputs of `committee_assignment` in declaration order: msg, participant_count and
+/// pub_keys_hash. A verifier obtains these from the chain, not from the prover.
+fn committee_public_input() -> Vec<u32> {
+    let mut public_input = vec![0x56; 32];
+    public_input.push(512);
+    let key_hash = committee_key_hash(&committee_members(), &committee_weights());
+    public_input.extend(key_hash.iter().map(|b| *b as u32));
+    public_input
+}
+
+/// Builds the min-sig counterpart of `committee_assignment`: member `i` holds `[2i + 1]pk` in G2
+/// and `[2i + 1]sig` in G1 over the same message, signed under `BLS_MIN_SIG_CIPHERSUITE`.
+fn min_sig_committee_assignment() -> BLSMinSigGKRCircuit<M31> {
+    let mut assignment = BLSMinSigGKRCircuit::<M31> {
+        pub_keys: [[[[M31::from(0); 48]; 2]; 2]; 512],
+        sigs: [[[M31::from(0); 48]; 2]; 512],
+        msg: [M31::from(0x56); 32],
+    };
+
+    let sk = Fr::from(0x5eed);
+    let pub_key = NativeG2::generator() * sk;
+    let sig = native::hash_to_g1(&[0x56; 32], BLS_MIN_SIG_CIPHERSUITE.dst().unwrap()) * sk;
 
-    // Initialize all 512 public keys with the same values
     for idx in 0..512 {
-        for i in 0..48 {
-            assignment.pub_keys[idx][0][i] = M31::from(pub_key_x_bytes.1[i] as u32);
-            assignment.pub_keys[idx][1][i] = M31::from(pub_key_y_bytes.1[i] as u32);
-        }
+        let k = Fr::from(2 * idx as u64 + 1);
+        native::g2_to_limbs(&(pub_key * k).into_affine(), &mut assignment.pub_keys[idx]);
+        native::g1_to_limbs(&(sig * k).into_affine(), &mut assignment.sigs[idx]);
     }
+    assignment
+}
 
-    // Initialize all 512 signatures with the same values
-    for idx in 0..512 {
-        for i in 0..48 {
-            assignment.sigs[idx][0][0][i] = M31::from(sig_x0_bytes.1[i] as u32);
-            assignment.sigs[idx][0][1][i] = M31::from(sig_x1_bytes.1[i] as u32);
-            assignment.sigs[idx][1][0][i] = M31::from(sig_y0_bytes.1[i] as u32);
-            assignment.sigs[idx][1][1][i] = M31::from(sig_y1_bytes.1[i] as u32);
-        }
+#[test]
+fn test_aggregate_pairing_check_gkr() {
+    println!("testing test_aggregate_pairing_check_gkr.....");
+    let mut hint_registry = HintRegistry::<M31>::new();
+    register_hint(&mut hint_registry);
+    register_bls12381_hint(&mut hint_registry);
+    let assignment = committee_assignment();
+
+    debug_eval(
+        &BLSSignatureGKRCircuit::default(),
+        &assignment,
+        hint_registry,
+    );
+}
+
+#[test]
+fn test_duplicate_keys_pairing_check_gkr() {
+    let mut hint_registry = HintRegistry::<M31>::new();
+    register_hint(&mut hint_registry);
+    register_bls12381_hint(&mut hint_registry);
+    //a key registered twice puts equal points in a row of the sum
+    let mut members = committee_members();
+    members[1] = members[0];
+    members[2] = members[0];
+    let assignment = committee_assignment_for(&members);
+
+    debug_eval(
+        &BLSSignatureGKRCircuit::default(),
+        &assignment,
+        hint_registry,
+    );
+}
+
+#[test]
+fn test_partial_participation_pairing_check_gkr() {
+    let mut hint_registry = HintRegistry::<M31>::new();
+    register_hint(&mut hint_registry);
+    register_bls12381_hint(&mut hint_registry);
+    let mut assignment = committee_assignment();
+    //members 0, 4, 8, .. weigh 1, so 1152 of 1280 still sign
+    for i in (0..512).step_by(4) {
+        assignment.participation[i] = M31::from(0);
     }
+    assignment.participant_count = M31::from(512 - 128);
 
-    // Initialize message
-    let msg_bigint = BigInt::from_str_radix(
-        "5656565656565656565656565656565656565656565656565656565656565656",
-        16,
-    )
-    .unwrap();
-    let msg_bytes = msg_bigint.to_bytes_be();
+    debug_eval(
+        &BLSSignatureGKRCircuit::default(),
+        &assignment,
+        hint_registry,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_participation_rejects_wrong_count() {
+    let mut hint_registry = HintRegistry::<M31>::new();
+    register_hint(&mut hint_registry);
+    register_bls12381_hint(&mut hint_registry);
+    let mut assignment = committee_assignment();
+    assignment.participant_count = M31::from(511);
+
+    debug_eval(
+        &BLSSignatureGKRCircuit::default(),
+        &assignment,
+        hint_registry,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_participation_rejects_missing_quorum() {
+    let mut hint_registry = HintRegistry::<M31>::new();
+    register_hint(&mut hint_registry);
+    register_bls12381_hint(&mut hint_registry);
+    let mut assignment = committee_assignment();
+    //the remaining signature is valid, but 768 of 1280 is below 2/3
+    for i in (0..512).step_by(2) {
+        assignment.participation[i] = M31::from(0);
+    }
+    assignment.participant_count = M31::from(256);
 
-    for i in 0..32 {
-        assignment.msg[i] = M31::from(msg_bytes.1[i] as u32);
+    debug_eval(
+        &BLSSignatureGKRCircuit::default(),
+        &assignment,
+        hint_registry,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_participation_rejects_substituted_weights() {
+    let mut hint_registry = HintRegistry::<M31>::new();
+    register_hint(&mut hint_registry);
+    register_bls12381_hint(&mut hint_registry);
+    let mut assignment = committee_assignment();
+    //half the committee signs and the prover zeroes the weight of the other half, which would
+    //meet the quorum but no longer matches the committed hash
+    for i in (0..512).step_by(2) {
+        assignment.participation[i] = M31::from(0);
+        assignment.weights[i] = M31::from(0);
     }
+    assignment.participant_count = M31::from(256);
 
     debug_eval(
         &BLSSignatureGKRCircuit::default(),
@@ -290,6 +506,20 @@ fn test_aggregate_pairing_check_gkr() {
     );
 }
 
+#[test]
+fn test_min_sig_aggregate_pairing_check_gkr() {
+    let mut hint_registry = HintRegistry::<M31>::new();
+    register_hint(&mut hint_registry);
+    register_bls12381_hint(&mut hint_registry);
+    let assignment = min_sig_committee_assignment();
+
+    debug_eval(
+        &BLSMinSigGKRCircuit::default(),
+        &assignment,
+        hint_registry,
+    );
+}
+
 fn compile_and_save_circuit() {
     println!("Beginning compilation....");
     let compile_result = compile_generic(
@@ -315,92 +545,94 @@ fn compile_and_save_circuit() {
 }
 
 fn main() {
-    // Parse command line arguments to get the number of assignments
+    // Parse command line arguments to get the number of assignments and the signature scheme
     let args: Vec<String> = env::args().collect();
     let num_assignments = if args.len() > 1 {
         args[1].parse::<usize>().unwrap_or(64)
     } else {
         64 // Default value if no argument is provided
     };
-    
+    let scheme = args.get(2).map(String::as_str).unwrap_or("bls");
+
+    match scheme {
+        "bls" => run_benchmark(
+            &BLSSignatureGKRCircuit::default(),
+            committee_assignment,
+            committee_public_input,
+            num_assignments,
+        ),
+        "bls-minsig" => run_benchmark(
+            &BLSMinSigGKRCircuit::default(),
+            min_sig_committee_assignment,
+            Vec::new,
+            num_assignments,
+        ),
+        "bls-batch" => run_benchmark(
+            &BLSBatchVerifyCircuit::default(),
+            bls_batch_reference_assignment,
+            Vec::new,
+            num_assignments,
+        ),
+        "light-client" => run_benchmark(
+            &LightClientUpdateCircuit::default(),
+            light_client_reference_assignment,
+            light_client_reference_public_input,
+            num_assignments,
+        ),
+        "light-client-rotation" => run_benchmark(
+            &LightClientRotationCircuit::default(),
+            rotation_reference_assignment,
+            rotation_reference_public_input,
+            num_assignments,
+        ),
+        "ed25519" => run_benchmark(
+            &Ed25519VerifyCircuit::default(),
+            reference_assignment,
+            Vec::new,
+            num_assignments,
+        ),
+        "ed25519-batch" => run_benchmark(
+            &Ed25519BatchVerifyCircuit::default(),
+            batch_reference_assignment,
+            Vec::new,
+            num_assignments,
+        ),
+        other => panic!(
+            "unknown scheme {}, expected bls, bls-minsig, bls-batch, light-client, light-client-rotation, ed25519 or \
+             ed25519-batch",
+            other
+        ),
+    }
+    MPIConfig::finalize();
+}
+
+fn register_all_hints(hint_registry: &mut HintRegistry<M31>) {
+    register_hint(hint_registry);
+    register_bls12381_hint(hint_registry);
+    register_ed25519_hint(hint_registry);
+}
+
+/// Compiles `circuit`, solves `num_assignments` copies of `assign()` in chunks of 16 and proves
+/// and verifies every chunk against the public inputs returned by `public_input()`.
+fn run_benchmark<Cir, Asg>(
+    circuit: &Cir,
+    assign: fn() -> Asg,
+    public_input: fn() -> Vec<u32>,
+    num_assignments: usize,
+)
+where
+    Cir: internal::DumpLoadTwoVariables<Variable> + GenericDefine<M31Config> + Clone,
+    Asg: internal::DumpLoadTwoVariables<M31> + Clone + Send + 'static,
+{
     println!("Running benchmark with {} assignments...", num_assignments);
     
     println!("Beginning compilation....");
-    let compile_result = compile_generic(
-        &BLSSignatureGKRCircuit::default(),
-        CompileOptions::default(),
-    )
-    .unwrap();
+    let compile_result = compile_generic(circuit, CompileOptions::default()).unwrap();
     println!("Compilation finished....");
 
     println!("Beginning assignment....");
     let start_time = std::time::Instant::now();
-    let mut hint_registry = HintRegistry::<M31>::new();
-    register_hint(&mut hint_registry);
-    let mut assignment = BLSSignatureGKRCircuit::<M31> {
-        g1_gen: [[M31::from(0); 48]; 2],
-        pub_keys: [[[M31::from(0); 48]; 2]; 512],
-        sigs: [[[[M31::from(0); 48]; 2]; 2]; 512],
-        msg: [M31::from(0); 32],
-    };
-
-    // Generator point
-    let g1_a0_bigint = BigInt::from_str_radix("3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507", 10).unwrap();
-    let g1_a1_bigint = BigInt::from_str_radix("1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569", 10).unwrap();
-    let g1_gen_x_bytes = g1_a0_bigint.to_bytes_le();
-    let g1_gen_y_bytes = g1_a1_bigint.to_bytes_le();
-
-    // Public key values (original)
-    let pub_key_a0_bigint = BigInt::from_str_radix("703326716001809064498853055672224052496326539572945177471956754169145471346922036117906423749397590945884354901914", 10).unwrap();
-    let pub_key_a1_bigint = BigInt::from_str_radix("3663539438728798306657207296627492219061036278584663040302088465810128029049191527519011271732625440307576496404164", 10).unwrap();
-    let pub_key_x_bytes = pub_key_a0_bigint.to_bytes_le();
-    let pub_key_y_bytes = pub_key_a1_bigint.to_bytes_le();
-
-    // Signature values (original)
-    let sig_b0_a0_bigint = BigInt::from_str_radix("1139568035424369576886504746727692711007568237996931740990083083283390253765157354611656848983901259161392255747051", 10).unwrap();
-    let sig_b0_a1_bigint = BigInt::from_str_radix("1254875381135194965686121199970220725987247367099176854373369962826503465173652424031228084897653264386315457499586", 10).unwrap();
-    let sig_b1_a0_bigint = BigInt::from_str_radix("3985022954582833583610752589867406089888552465191016224317399257491285114232241437209840121250502955373920952884061", 10).unwrap();
-    let sig_b1_a1_bigint = BigInt::from_str_radix("249179071186755291707558993402509484460063614865425349660967954952959359540889904597635495866422932058900651130506", 10).unwrap();
-    let sig_x0_bytes = sig_b0_a0_bigint.to_bytes_le();
-    let sig_x1_bytes = sig_b0_a1_bigint.to_bytes_le();
-    let sig_y0_bytes = sig_b1_a0_bigint.to_bytes_le();
-    let sig_y1_bytes = sig_b1_a1_bigint.to_bytes_le();
-
-    // Initialize generator point
-    for i in 0..48 {
-        assignment.g1_gen[0][i] = M31::from(g1_gen_x_bytes.1[i] as u32);
-        assignment.g1_gen[1][i] = M31::from(g1_gen_y_bytes.1[i] as u32);
-    }
-
-    // Initialize all 512 public keys with the same values
-    for idx in 0..512 {
-        for i in 0..48 {
-            assignment.pub_keys[idx][0][i] = M31::from(pub_key_x_bytes.1[i] as u32);
-            assignment.pub_keys[idx][1][i] = M31::from(pub_key_y_bytes.1[i] as u32);
-        }
-    }
-
-    // Initialize all 512 signatures with the same values
-    for idx in 0..512 {
-        for i in 0..48 {
-            assignment.sigs[idx][0][0][i] = M31::from(sig_x0_bytes.1[i] as u32);
-            assignment.sigs[idx][0][1][i] = M31::from(sig_x1_bytes.1[i] as u32);
-            assignment.sigs[idx][1][0][i] = M31::from(sig_y0_bytes.1[i] as u32);
-            assignment.sigs[idx][1][1][i] = M31::from(sig_y1_bytes.1[i] as u32);
-        }
-    }
-
-    // Initialize message
-    let msg_bigint = BigInt::from_str_radix(
-        "5656565656565656565656565656565656565656565656565656565656565656",
-        16,
-    )
-    .unwrap();
-    let msg_bytes = msg_bigint.to_bytes_be();
-
-    for i in 0..32 {
-        assignment.msg[i] = M31::from(msg_bytes.1[i] as u32);
-    }
+    let assignment = assign();
     let end_time = std::time::Instant::now();
     println!(
         "assigned assignments time: {:?}",
@@ -411,7 +643,7 @@ fn main() {
 
     println!("Beginning witness generation....");
     let witness_gen_start_time = Instant::now();
-    let assignment_chunks: Vec<Vec<BLSSignatureGKRCircuit<M31>>> =
+    let assignment_chunks: Vec<Vec<Asg>> =
         assignments.chunks(16).map(|x| x.to_vec()).collect();
     let witness_solver = Arc::new(compile_result.witness_solver);
     let handles = assignment_chunks
@@ -421,7 +653,7 @@ fn main() {
             let witness_solver = Arc::clone(&witness_solver);
             thread::spawn(move || {
                 let mut hint_registry1 = HintRegistry::<M31>::new();
-                register_hint(&mut hint_registry1);
+                register_all_hints(&mut hint_registry1);
                 let witness = witness_solver
                     .solve_witnesses_with_hints(&assignments, &mut hint_registry1)
                     .unwrap();
@@ -457,6 +689,7 @@ fn main() {
     println!("Starting proof generation for {} witness files...", assignment_count);
     
     let circuit_path_str = circuit_path.to_string();
+    let expected_public_input = public_input();
     
     // Create a channel to collect timing information from threads
     let (tx, rx) = std::sync::mpsc::channel();
@@ -467,13 +700,15 @@ fn main() {
             let proof_path = format!("proof_{}.bin", i);
             let circuit_path_clone = circuit_path_str.clone();
             let tx_clone = tx.clone();
+            let expected_public_input = expected_public_input.clone();
             
             thread::spawn(move || {
                 println!("Starting proof generation for witness file {}", witness_path);
                 let (proving_time, verification_time) = prove_and_verify(
                     &circuit_path_clone,
                     &witness_path,
-                    Some(&proof_path)
+                    Some(&proof_path),
+                    &expected_public_input,
                 );
                 tx_clone.send((proving_time, verification_time)).unwrap();
                 println!("Proof generation completed for witness file {}", witness_path);
@@ -517,11 +752,17 @@ fn main() {
     println!("+{:-^15}+{:-^15}+{:-^15}+{:-^15}+", "", "", "", "");
     
     println!("\nAll proofs generated and verified successfully!");
-    MPIConfig::finalize();
 }
 
-/// Creates a prover and verifier for the BLS signature circuit
-pub fn prove_and_verify(circuit_path: &str, witness_path: &str, write_proof_to: Option<&str>) -> (Duration, Duration) {
+/// Proves the witness of a compiled signature circuit and verifies the proof. The proof is only
+/// accepted for the public inputs in `expected_public_input`, which every assignment of the
+/// witness must share.
+pub fn prove_and_verify(
+    circuit_path: &str,
+    witness_path: &str,
+    write_proof_to: Option<&str>,
+    expected_public_input: &[u32],
+) -> (Duration, Duration) {
     let mpi_config = MPIConfig::new();
     
     // Define the GKR config for M31 field with Keccak256 hasher and Raw polynomial commitment
@@ -615,6 +856,19 @@ pub fn prove_and_verify(circuit_path: &str, witness_path: &str, write_proof_to:
     
     // Verify the proof
     if config.mpi_config.is_root() {
+        // A valid proof over public inputs the caller did not expect proves nothing to them
+        assert_eq!(circuit.public_input.len(), expected_public_input.len());
+        for (value, expected) in public_input_gathered
+            .iter()
+            .zip(expected_public_input.iter().cycle())
+        {
+            let expected =
+                <<BLSConfig as GKRConfig>::FieldConfig as GKRFieldConfig>::CircuitField::from_u32(
+                    *expected,
+                );
+            assert!(value.unpack().iter().all(|lane| *lane == expected));
+        }
+
         // Write the proof to a file if requested
         if let Some(str) = write_proof_to {
             let mut file = fs::OpenOptions::new()
@@ -0,0 +1,302 @@
+use expander_compiler::{
+    declare_circuit,
+    frontend::{Config, GenericDefine, M31Config, PublicVariable, RootAPI, Variable},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::bls12_381::g1::{G1Affine, G1};
+use crate::hash::sha256::sha256;
+
+// SSZ merkleization (consensus-specs ssz/simple-serialize.md) over 32-byte chunks given as byte
+// variables. Every length is a build-time constant, so no gadget needs length mixing or
+// variable padding.
+
+pub const SYNC_COMMITTEE_SIZE: usize = 512;
+
+pub fn hash_pair<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    left: &[Variable],
+    right: &[Variable],
+) -> Vec<Variable> {
+    sha256(native, &[left, right].concat())
+}
+
+// merkleize hashes a power-of-two number of chunks up to their root
+pub fn merkleize<C: Config, B: RootAPI<C>>(native: &mut B, chunks: &[Vec<Variable>]) -> Vec<Variable> {
+    assert!(chunks.len().is_power_of_two(), "merkleize expects 2^k chunks");
+    let mut layer = chunks.to_vec();
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(native, &pair[0], &pair[1]))
+            .collect();
+    }
+    layer.pop().unwrap()
+}
+
+// pubkey_root is hash_tree_root(BLSPubkey): the 48 bytes fill two chunks, the second one
+// right-padded with zeros
+pub fn pubkey_root<C: Config, B: RootAPI<C>>(native: &mut B, pubkey: &[Variable]) -> Vec<Variable> {
+    let zero = native.constant(0);
+    let second = [&pubkey[32..48], &[zero; 16][..]].concat();
+    hash_pair(native, &pubkey[..32], &second)
+}
+
+// merkle_branch_root folds leaf with its branch, sibling i sitting at depth i from the bottom,
+// and returns the root. index is the position of the leaf among the 2^branch.len() leaves.
+pub fn merkle_branch_root<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    leaf: &[Variable],
+    branch: &[[Variable; 32]],
+    index: usize,
+) -> Vec<Variable> {
+    let mut value = leaf.to_vec();
+    for (i, sibling) in branch.iter().enumerate() {
+        value = if (index >> i) & 1 == 1 {
+            hash_pair(native, sibling, &value)
+        } else {
+            hash_pair(native, &value, sibling)
+        };
+    }
+    value
+}
+
+// uint64_chunk is the chunk of an SSZ uint64 given as 8 little-endian bytes
+pub fn uint64_chunk<C: Config, B: RootAPI<C>>(native: &mut B, value: &[Variable]) -> Vec<Variable> {
+    let zero = native.constant(0);
+    [value, &[zero; 24][..]].concat()
+}
+
+// beacon_block_header_root is hash_tree_root(BeaconBlockHeader), whose five fields are padded to
+// eight leaves
+pub fn beacon_block_header_root<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    slot: &[Variable],
+    proposer_index: &[Variable],
+    parent_root: &[Variable],
+    state_root: &[Variable],
+    body_root: &[Variable],
+) -> Vec<Variable> {
+    let zero = vec![native.constant(0); 32];
+    let leaves = vec![
+        uint64_chunk(native, slot),
+        uint64_chunk(native, proposer_index),
+        parent_root.to_vec(),
+        state_root.to_vec(),
+        body_root.to_vec(),
+        zero.clone(),
+        zero.clone(),
+        zero,
+    ];
+    merkleize(native, &leaves)
+}
+
+// compute_domain is domain_type || hash_tree_root(ForkData{fork_version, genesis_validators_root})[..28]
+pub fn compute_domain<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    domain_type: [u8; 4],
+    fork_version: &[Variable],
+    genesis_validators_root: &[Variable],
+) -> Vec<Variable> {
+    let zero = native.constant(0);
+    let version_chunk = [fork_version, &[zero; 28][..]].concat();
+    let fork_data_root = hash_pair(native, &version_chunk, genesis_validators_root);
+    let mut domain = domain_type
+        .iter()
+        .map(|b| native.constant(*b as u32))
+        .collect::<Vec<_>>();
+    domain.extend_from_slice(&fork_data_root[..28]);
+    domain
+}
+
+// compute_signing_root is hash_tree_root(SigningData{object_root, domain})
+pub fn compute_signing_root<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    object_root: &[Variable],
+    domain: &[Variable],
+) -> Vec<Variable> {
+    hash_pair(native, object_root, domain)
+}
+
+// sync_committee_root is hash_tree_root(SyncCommittee{pubkeys, aggregate_pubkey})
+pub fn sync_committee_root<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    pubkeys: &[[Variable; 48]],
+    aggregate_pubkey: &[Variable],
+) -> Vec<Variable> {
+    let leaves = pubkeys
+        .iter()
+        .map(|pk| pubkey_root(native, pk))
+        .collect::<Vec<_>>();
+    let pubkeys_root = merkleize(native, &leaves);
+    let aggregate_root = pubkey_root(native, aggregate_pubkey);
+    hash_pair(native, &pubkeys_root, &aggregate_root)
+}
+
+// decode_sync_committee uncompresses every committee key, which also checks it is in G1, so the
+// returned points are exactly the ones committed to by the returned root
+pub fn decode_sync_committee<C: Config, B: RootAPI<C>>(
+    native: &mut B,
+    g1: &mut G1,
+    pubkeys: &[[Variable; 48]],
+    aggregate_pubkey: &[Variable],
+) -> (Vec<G1Affine>, Vec<Variable>) {
+    let mut points = vec![];
+    for pk in pubkeys.iter() {
+        points.push(g1.uncompressed(native, pk));
+    }
+    let root = sync_committee_root(native, pubkeys, aggregate_pubkey);
+    (points, root)
+}
+
+// Out-of-circuit counterparts of the gadgets, used to build assignments.
+
+pub fn native_hash_pair(left: &[u8], right: &[u8]) -> Vec<u8> {
+    Sha256::digest([left, right].concat()).to_vec()
+}
+
+pub fn native_merkleize(chunks: &[Vec<u8>]) -> Vec<u8> {
+    assert!(chunks.len().is_power_of_two(), "merkleize expects 2^k chunks");
+    let mut layer = chunks.to_vec();
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| native_hash_pair(&pair[0], &pair[1]))
+            .collect();
+    }
+    layer.pop().unwrap()
+}
+
+pub fn native_merkle_branch_root(leaf: &[u8], branch: &[Vec<u8>], index: usize) -> Vec<u8> {
+    let mut value = leaf.to_vec();
+    for (i, sibling) in branch.iter().enumerate() {
+        value = if (index >> i) & 1 == 1 {
+            native_hash_pair(sibling, &value)
+        } else {
+            native_hash_pair(&value, sibling)
+        };
+    }
+    value
+}
+
+pub fn native_pubkey_root(pubkey: &[u8]) -> Vec<u8> {
+    native_hash_pair(&pubkey[..32], &[&pubkey[32..48], &[0u8; 16][..]].concat())
+}
+
+pub fn native_sync_committee_root(pubkeys: &[Vec<u8>], aggregate_pubkey: &[u8]) -> Vec<u8> {
+    let leaves = pubkeys.iter().map(|pk| native_pubkey_root(pk)).collect::<Vec<_>>();
+    native_hash_pair(&native_merkleize(&leaves), &native_pubkey_root(aggregate_pubkey))
+}
+
+pub fn native_beacon_block_header_root(
+    slot: u64,
+    proposer_index: u64,
+    parent_root: &[u8],
+    state_root: &[u8],
+    body_root: &[u8],
+) -> Vec<u8> {
+    let uint64_chunk = |v: u64| [&v.to_le_bytes()[..], &[0u8; 24][..]].concat();
+    native_merkleize(&[
+        uint64_chunk(slot),
+        uint64_chunk(proposer_index),
+        parent_root.to_vec(),
+        state_root.to_vec(),
+        body_root.to_vec(),
+        vec![0; 32],
+        vec![0; 32],
+        vec![0; 32],
+    ])
+}
+
+pub fn native_compute_domain(
+    domain_type: [u8; 4],
+    fork_version: &[u8],
+    genesis_validators_root: &[u8],
+) -> Vec<u8> {
+    let version_chunk = [fork_version, &[0u8; 28][..]].concat();
+    let fork_data_root = native_hash_pair(&version_chunk, genesis_validators_root);
+    [&domain_type[..], &fork_data_root[..28]].concat()
+}
+
+declare_circuit!(SyncCommitteeRootCircuit {
+    pubkeys: [[Variable; 48]; SYNC_COMMITTEE_SIZE],
+    aggregate_pubkey: [Variable; 48],
+    root: [PublicVariable; 32],
+});
+
+impl GenericDefine<M31Config> for SyncCommitteeRootCircuit<Variable> {
+    fn define<Builder: RootAPI<M31Config>>(&self, builder: &mut Builder) {
+        let mut g1 = G1::new(builder);
+        let (_, root) = decode_sync_committee(builder, &mut g1, &self.pubkeys, &self.aggregate_pubkey);
+        for (byte, expected) in root.iter().zip(self.root.iter()) {
+            builder.assert_is_equal(*byte, *expected);
+        }
+        g1.curve_f.check_mul(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+        g1.curve_f.table.final_check(builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls12_381::native::compress_g1;
+    use ark_bls12_381::{Fr, G1Affine as NativeG1, G1Projective};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::Zero;
+    use circuit_std_rs::utils::register_hint;
+    use expander_compiler::frontend::*;
+    use expander_compiler::frontend::{HintRegistry, M31};
+    use extra::debug_eval;
+
+    fn sync_committee_assignment() -> SyncCommitteeRootCircuit<M31> {
+        let mut assignment = SyncCommitteeRootCircuit::<M31> {
+            pubkeys: [[M31::from(0); 48]; SYNC_COMMITTEE_SIZE],
+            aggregate_pubkey: [M31::from(0); 48],
+            root: [M31::from(0); 32],
+        };
+        let mut aggregate = G1Projective::zero();
+        let mut pubkeys = vec![];
+        for i in 0..SYNC_COMMITTEE_SIZE {
+            let pk = (NativeG1::generator() * Fr::from(0x5eed + i as u64)).into_affine();
+            aggregate += pk;
+            let bytes = compress_g1(&pk);
+            for (limb, byte) in assignment.pubkeys[i].iter_mut().zip(bytes.iter()) {
+                *limb = M31::from(*byte as u32);
+            }
+            pubkeys.push(bytes);
+        }
+        let aggregate_bytes = compress_g1(&aggregate.into_affine());
+        for (limb, byte) in assignment.aggregate_pubkey.iter_mut().zip(aggregate_bytes.iter()) {
+            *limb = M31::from(*byte as u32);
+        }
+        let root = native_sync_committee_root(&pubkeys, &aggregate_bytes);
+        for (limb, byte) in assignment.root.iter_mut().zip(root.iter()) {
+            *limb = M31::from(*byte as u32);
+        }
+        assignment
+    }
+
+    #[test]
+    fn test_sync_committee_root() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        debug_eval(
+            &SyncCommitteeRootCircuit::default(),
+            &sync_committee_assignment(),
+            hint_registry,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sync_committee_root_rejects_other_root() {
+        let mut hint_registry = HintRegistry::<M31>::new();
+        register_hint(&mut hint_registry);
+        let mut assignment = sync_committee_assignment();
+        assignment.root[31] = assignment.root[31] + M31::from(1);
+        debug_eval(&SyncCommitteeRootCircuit::default(), &assignment, hint_registry);
+    }
+}